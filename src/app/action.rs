@@ -1,6 +1,28 @@
+use std::sync::Arc;
+
 use crossterm::event::{KeyEvent, MouseEvent};
 
-use crate::data::{Post, PostDetails};
+use super::Instance;
+use crate::{data::{Post, PostComment, PostDetails}, modes::Mode};
+
+/// Request to load something from the given instance.
+pub type Request<T> = (Arc<Instance>, T, Purpose);
+/// Request to load the posts (or comments) for a mode, on the given instance.
+pub type PostsRequest = Request<Mode>;
+/// Request to load the details for the post with the given short ID, on the
+/// given instance.
+pub type DetailsRequest = Request<String>;
+
+/// Why something is being loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    /// To be browsed
+    Load,
+    /// Quietly, to refresh what is being browsed once it has expired
+    Refresh,
+    /// In the background, in case it is browsed later
+    Prefetch,
+}
 
 #[derive(Debug)]
 pub enum Action {
@@ -8,12 +30,44 @@ pub enum Action {
     Resize(u16, u16),
     Key(KeyEvent),
     Mouse(MouseEvent),
-    LoadPosts(Vec<Post>),
-    LoadPostDetails(PostDetails),
+    /// Posts loaded for the given mode, which may no longer be the current
+    /// one, along with the number of them which are hidden
+    LoadPosts(Arc<Instance>, Mode, Vec<Post>, usize),
+    /// Comments loaded for the given mode, which may no longer be the current
+    /// one
+    LoadComments(Arc<Instance>, Mode, Vec<PostComment>),
+    LoadPostDetails(Arc<Instance>, PostDetails),
+    /// Fetching data from the given instance failed and is being retried, with
+    /// the given number of attempts left
+    Retrying(Arc<Instance>, Fetch, u32),
+    /// Fetching data from the given instance failed
+    FetchFailed(Arc<Instance>, Fetch, color_eyre::Report),
+}
+
+/// Kinds of data fetched from an instance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetch {
+    /// Posts for the given mode, or comments for modes which list comments
+    Posts(Mode),
+    PostDetails,
 }
 
 #[derive(Debug)]
 pub enum DatabaseAction {
-    MarkPostRead(String),
-    MarkPostUnread(String),
+    /// Mark the post read, on the instance with the given base URL
+    MarkPostRead { instance: String, post: Box<Post> },
+    /// Mark the post with the given ID unread, on the instance with the given
+    /// base URL
+    MarkPostUnread { instance: String, id: String },
+    /// Save the post to read later, on the instance with the given base URL
+    SavePost { instance: String, post: Box<Post> },
+    /// Remove the post with the given ID from the posts saved to read later,
+    /// on the instance with the given base URL
+    UnsavePost { instance: String, id: String },
+    /// Hide the post with the given ID from listings, on the instance with the
+    /// given base URL
+    HidePost { instance: String, id: String },
+    /// Stop hiding the post with the given ID from listings, on the instance
+    /// with the given base URL
+    UnhidePost { instance: String, id: String },
 }
@@ -0,0 +1,681 @@
+//! Commands - everything which can be done while browsing, whether triggered
+//! by a key, the mouse or the command palette.
+use std::sync::{atomic::Ordering, mpsc::Sender};
+
+use color_eyre::Result;
+
+use super::{App, action::{DatabaseAction, DetailsRequest, PostsRequest}};
+use crate::{config::KeyAction, modes::Mode};
+
+/// Channels to the threads which load results and update the database.
+pub struct Channels {
+    pub posts: Sender<PostsRequest>,
+    pub details: Sender<DetailsRequest>,
+    pub db: Sender<DatabaseAction>,
+}
+
+/// Something which can be done while browsing. Unless given an index, commands
+/// act on the selected row (or the selected comment in the details popup).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    NextRow,
+    PreviousRow,
+    FirstRow,
+    LastRow,
+    /// Select the row at the given index
+    Select(usize),
+    PreviousPage,
+    NextPage,
+    /// Go to the given (1-indexed) page
+    GoToPage(u8),
+    NextMode,
+    PreviousMode,
+    SetMode(Mode),
+    NextTopPeriod,
+    NextInstance,
+    OpenDomain,
+    UserStories,
+    UserThreads,
+    History,
+    Saved,
+    NextSavedSort,
+    /// Open the selected post, or comment
+    Open,
+    /// Open the post at the given index
+    OpenPost(usize),
+    /// Open the post with the given short ID
+    OpenById(String),
+    /// Open the comments for the selected post, or the story for the selected
+    /// comment
+    OpenComments,
+    MarkRead,
+    MarkUnread,
+    Save,
+    Unsave,
+    Hide,
+    UndoHide,
+    ToggleShowHidden,
+    ToggleTagFilterAction,
+    PreviousTag,
+    NextTag,
+    MuteTag,
+    OnlyTag,
+    ToggleDetails,
+    ToggleFilters,
+    ToggleStats,
+    ToggleKeybinds,
+    OpenPalette,
+    /// Close any popups, and dismiss errors
+    ClosePopups,
+    Refresh,
+    /// Start typing a search, listing only the rows matching it
+    Search,
+    NextMatch,
+    PreviousMatch,
+    /// Open the Search view, searching everything seen for the given query
+    SearchSeen(String),
+    Quit,
+    ForceQuit,
+}
+
+impl Command {
+    /// Whether the command opens or closes a popup shown over the others, which
+    /// are closed by any other command.
+    pub fn toggles_popup(&self) -> bool {
+        matches!(
+            self,
+            Self::ToggleKeybinds | Self::ToggleStats | Self::ToggleFilters | Self::OpenPalette
+        )
+    }
+
+    /// Whether the command changes the rows listed, other than by searching
+    /// them, which clears the search first.
+    pub fn changes_listing(&self) -> bool {
+        matches!(
+            self,
+            Self::PreviousPage
+                | Self::NextPage
+                | Self::GoToPage(_)
+                | Self::NextMode
+                | Self::PreviousMode
+                | Self::SetMode(_)
+                | Self::NextTopPeriod
+                | Self::NextInstance
+                | Self::OpenDomain
+                | Self::UserStories
+                | Self::UserThreads
+                | Self::History
+                | Self::Saved
+                | Self::NextSavedSort
+                | Self::UndoHide
+                | Self::ToggleShowHidden
+                | Self::ToggleTagFilterAction
+                | Self::MuteTag
+                | Self::OnlyTag
+                | Self::Refresh
+        )
+    }
+}
+
+impl From<KeyAction> for Command {
+    fn from(action: KeyAction) -> Self {
+        match action {
+            KeyAction::NextRow => Self::NextRow,
+            KeyAction::PreviousRow => Self::PreviousRow,
+            KeyAction::LastRow => Self::LastRow,
+            KeyAction::FirstRow => Self::FirstRow,
+            KeyAction::PreviousPage => Self::PreviousPage,
+            KeyAction::NextPage => Self::NextPage,
+            KeyAction::NextMode => Self::NextMode,
+            KeyAction::PreviousMode => Self::PreviousMode,
+            KeyAction::NextTopPeriod => Self::NextTopPeriod,
+            KeyAction::NextInstance => Self::NextInstance,
+            KeyAction::OpenDomain => Self::OpenDomain,
+            KeyAction::UserStories => Self::UserStories,
+            KeyAction::UserThreads => Self::UserThreads,
+            KeyAction::History => Self::History,
+            KeyAction::Saved => Self::Saved,
+            KeyAction::NextSavedSort => Self::NextSavedSort,
+            KeyAction::OpenPost => Self::Open,
+            KeyAction::OpenComments => Self::OpenComments,
+            KeyAction::MarkRead => Self::MarkRead,
+            KeyAction::MarkUnread => Self::MarkUnread,
+            KeyAction::Save => Self::Save,
+            KeyAction::Unsave => Self::Unsave,
+            KeyAction::Hide => Self::Hide,
+            KeyAction::UndoHide => Self::UndoHide,
+            KeyAction::ToggleShowHidden => Self::ToggleShowHidden,
+            KeyAction::ToggleTagFilterAction => Self::ToggleTagFilterAction,
+            KeyAction::ToggleFilters => Self::ToggleFilters,
+            KeyAction::PreviousTag => Self::PreviousTag,
+            KeyAction::NextTag => Self::NextTag,
+            KeyAction::MuteTag => Self::MuteTag,
+            KeyAction::OnlyTag => Self::OnlyTag,
+            KeyAction::ToggleDetails => Self::ToggleDetails,
+            KeyAction::ToggleStats => Self::ToggleStats,
+            KeyAction::ToggleKeybinds => Self::ToggleKeybinds,
+            KeyAction::ClosePopup => Self::ClosePopups,
+            KeyAction::Refresh => Self::Refresh,
+            KeyAction::Search => Self::Search,
+            KeyAction::NextMatch => Self::NextMatch,
+            KeyAction::PreviousMatch => Self::PreviousMatch,
+            KeyAction::SearchSeen => Self::SearchSeen(String::new()),
+            KeyAction::CommandPalette => Self::OpenPalette,
+            KeyAction::Quit => Self::Quit,
+            KeyAction::ForceQuit => Self::ForceQuit,
+        }
+    }
+}
+
+impl App {
+    /// Carry out the command.
+    pub(super) fn dispatch(&mut self, command: Command, tx: &Channels) -> Result<()> {
+        if command.changes_listing() {
+            self.clear_search();
+        }
+        let selected = self.posts_list_state.selected();
+
+        match command {
+            Command::Quit => self.is_running.store(false, Ordering::Release),
+            Command::ForceQuit => {
+                self.exit_code = 1;
+                self.is_running.store(false, Ordering::Release);
+            }
+
+            // POPUPS
+            // Clear the search first, leaving the details popup open if it
+            // was searching its comments
+            Command::ClosePopups if self.search.is_some() => self.clear_search(),
+            Command::ClosePopups => {
+                self.show_details_popup = false;
+                self.show_keybinds_popup = false;
+                self.stats_popup = None;
+                self.show_filters_popup = false;
+                self.fetch_error = None;
+            }
+            Command::ToggleKeybinds => {
+                self.show_keybinds_popup = !self.show_keybinds_popup;
+                self.stats_popup = None;
+                self.show_filters_popup = false;
+            }
+            Command::ToggleStats => {
+                self.toggle_stats_popup()?;
+                self.show_keybinds_popup = false;
+                self.show_filters_popup = false;
+            }
+            Command::ToggleFilters => {
+                if !self.mode.lists_comments() {
+                    self.toggle_filters_popup();
+                }
+                self.show_keybinds_popup = false;
+                self.stats_popup = None;
+            }
+            Command::OpenPalette => {
+                self.open_palette();
+                self.show_keybinds_popup = false;
+                self.stats_popup = None;
+                self.show_filters_popup = false;
+            }
+            Command::ToggleDetails => {
+                if let Some(index) = selected
+                    && !self.posts.is_empty()
+                {
+                    self.show_details_popup = !self.show_details_popup;
+                    if self.show_details_popup {
+                        self.load_post_comments(index, &tx.details, &tx.db)?;
+                        // The post itself is selected until moving down to its
+                        // comments
+                        self.comments_list_state.select(None);
+                        self.selected_tag = None;
+                    }
+                };
+            }
+
+            // FUNCTIONALITY
+            Command::Open => {
+                if self.show_details_popup && self.comments_list_state.selected().is_some() {
+                    self.open_comment()?;
+                } else if let Some(selected) = selected {
+                    if self.mode.lists_comments() {
+                        self.open_listed_comment(selected)?;
+                    } else {
+                        self.open_post(selected, &tx.db)?;
+                    }
+                }
+            }
+            Command::OpenPost(index) => self.open_post(index, &tx.db)?,
+            Command::OpenById(id) => self.open_post_by_id(&id, &tx.db)?,
+            Command::OpenComments => {
+                if let Some(selected) = selected {
+                    if self.mode.lists_comments() {
+                        self.open_listed_comment_story(selected)?;
+                    } else {
+                        self.open_post_comments(selected, &tx.db)?;
+                    }
+                }
+            }
+
+            Command::MarkRead => {
+                if let Some(selected) = selected {
+                    self.mark_post_read(selected, &tx.db)?;
+                }
+            }
+            Command::MarkUnread => {
+                if let Some(selected) = selected {
+                    self.mark_post_unread(selected, &tx.db)?;
+                }
+            }
+            Command::Save => {
+                if let Some(selected) = selected {
+                    self.save_post(selected, &tx.db)?;
+                }
+            }
+            Command::Unsave => {
+                if let Some(selected) = selected {
+                    self.unsave_post(selected, &tx.db)?;
+                }
+            }
+            Command::Hide => {
+                if let Some(selected) = selected {
+                    self.hide_post(selected, &tx.db)?;
+                }
+            }
+            Command::UndoHide => self.undo_hide(&tx.db)?,
+            Command::ToggleShowHidden => self.toggle_show_hidden(&tx.posts)?,
+
+            Command::Refresh => {
+                self.fetch_error = None;
+                if self.show_details_popup {
+                    let index =
+                        selected.expect("in details popup - relevant post should be selected");
+                    self.load_post_comments(index, &tx.details, &tx.db)?;
+                } else {
+                    self.load_posts(&tx.posts)?;
+                }
+            }
+
+            // NAVIGATION
+            Command::NextRow => self.next_row(),
+            Command::PreviousRow => self.previous_row(),
+            Command::FirstRow => self.first_row(),
+            Command::LastRow => self.last_row(),
+            Command::Select(index) => {
+                // The details popup shows the selected post
+                if !self.show_details_popup && index < self.list_len() {
+                    self.select_row(index);
+                }
+            }
+
+            // SEARCH
+            Command::Search => self.start_search(),
+            Command::NextMatch if self.search.is_some() => self.next_row(),
+            Command::PreviousMatch if self.search.is_some() => self.previous_row(),
+            Command::NextMatch | Command::PreviousMatch => {}
+            Command::SearchSeen(query) => {
+                self.open_search_seen(query)?;
+                self.show_keybinds_popup = false;
+                self.stats_popup = None;
+                self.show_filters_popup = false;
+            }
+
+            // Tags row of the details popup
+            Command::PreviousTag => self.previous_tag(),
+            Command::NextTag => self.next_tag(),
+            Command::MuteTag => self.toggle_tag_muted()?,
+            Command::OnlyTag => self.toggle_tag_only()?,
+            Command::ToggleTagFilterAction => self.toggle_tag_filter_action()?,
+
+            Command::PreviousPage => self.previous_page(&tx.posts)?,
+            Command::NextPage => self.next_page(&tx.posts)?,
+            Command::GoToPage(page) => self.go_to_page(page, &tx.posts)?,
+
+            Command::OpenDomain => {
+                if let Some(selected) = selected {
+                    self.open_domain(selected, &tx.posts)?;
+                }
+            }
+
+            Command::NextTopPeriod => self.next_top_period(&tx.posts)?,
+            Command::NextInstance => self.next_instance(&tx.posts)?,
+            Command::UserStories => self.open_user_stories(&tx.posts)?,
+            Command::UserThreads => self.open_user_threads(&tx.posts)?,
+            Command::History => self.open_history(&tx.posts)?,
+            Command::Saved => self.open_saved(&tx.posts)?,
+            Command::NextSavedSort => self.next_saved_sort(&tx.posts)?,
+
+            Command::NextMode => self.next_mode(&tx.posts)?,
+            Command::PreviousMode => self.prev_mode(&tx.posts)?,
+            Command::SetMode(mode) => self.set_mode(mode, &tx.posts)?,
+        };
+
+        // Comments searched in the details popup are all listed again once it
+        // is closed
+        if !self.show_details_popup && self.searches_details() {
+            self.clear_search();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use std::sync::{Arc, mpsc::{Receiver, channel}};
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+    use pretty_assertions::assert_eq;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::{app::{Instance, TABLE_ROW_HEIGHT}, config::Config, data::{test_comment, test_post}, database::{get_db_connection, init_test_db, queries::store_listing}};
+
+    pub(in crate::app) struct Receivers {
+        pub posts: Receiver<PostsRequest>,
+        pub details: Receiver<DetailsRequest>,
+        pub db: Receiver<DatabaseAction>,
+    }
+
+    /// App browsing a loaded page of three posts, without a terminal.
+    pub(in crate::app) fn app() -> (App, Channels, Receivers) {
+        let mut app = App::new(init_test_db(), Config::default()).unwrap();
+        app.replace_posts(vec![test_post("a"), test_post("b"), test_post("c")]);
+        app.is_loading.store(false, Ordering::Relaxed);
+
+        let (posts, rx_posts) = channel();
+        let (details, rx_details) = channel();
+        let (db, rx_db) = channel();
+        let channels = Channels { posts, details, db };
+        let receivers = Receivers {
+            posts: rx_posts,
+            details: rx_details,
+            db: rx_db,
+        };
+
+        (app, channels, receivers)
+    }
+
+    fn selected_id(app: &App) -> &str {
+        &app.current_post().unwrap().short_id
+    }
+
+    #[test]
+    fn navigation() {
+        let (mut app, tx, _rx) = app();
+        let mut dispatch = |command| {
+            app.dispatch(command, &tx).unwrap();
+            app.posts_list_state.selected()
+        };
+
+        assert_eq!(dispatch(Command::NextRow), Some(1));
+        assert_eq!(dispatch(Command::LastRow), Some(2));
+        // Wraps around
+        assert_eq!(dispatch(Command::NextRow), Some(0));
+        assert_eq!(dispatch(Command::PreviousRow), Some(2));
+        assert_eq!(dispatch(Command::Select(1)), Some(1));
+        assert_eq!(dispatch(Command::Select(3)), Some(1));
+        assert_eq!(dispatch(Command::FirstRow), Some(0));
+    }
+
+    #[test]
+    fn next_page_requested() {
+        let (mut app, tx, rx) = app();
+
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert_eq!(app.mode, Mode::Hottest(2));
+        assert!(app.is_loading.load(Ordering::Relaxed));
+
+        let (_, mode, _) = rx.posts.try_recv().unwrap();
+        assert_eq!(mode, Mode::Hottest(2));
+    }
+
+    #[test]
+    fn next_page_past_empty_page() {
+        let (mut app, tx, rx) = app();
+
+        // No more posts
+        app.replace_posts(Vec::new());
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert_eq!(app.mode, Mode::Hottest(1));
+
+        // Every post filtered out by its tags
+        app.config.tag_filter.only = vec!["go".to_string()];
+        let mut posts = vec![test_post("a"), test_post("b")];
+        app.filter_posts(&app.mode.clone(), &mut posts, 0);
+        app.replace_posts(posts);
+        assert_eq!(app.list_len(), 0);
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert_eq!(app.mode, Mode::Hottest(2));
+        assert_eq!(rx.posts.try_recv().unwrap().1, Mode::Hottest(2));
+
+        // Every post hidden by a filter rule
+        app.config.tag_filter.only.clear();
+        app.config.filters =
+            vec![toml::from_str("field = 'submitter'\npattern = 'user'\naction = 'hide'").unwrap()];
+        let mut posts = vec![test_post("d"), test_post("e")];
+        app.filter_posts(&app.mode.clone(), &mut posts, 0);
+        app.replace_posts(posts);
+        app.is_loading.store(false, Ordering::Relaxed);
+        assert_eq!(app.list_len(), 0);
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert_eq!(app.mode, Mode::Hottest(3));
+    }
+
+    #[test]
+    fn details_popup() {
+        let (mut app, tx, rx) = app();
+
+        app.dispatch(Command::Select(1), &tx).unwrap();
+        app.dispatch(Command::ToggleDetails, &tx).unwrap();
+        assert!(app.show_details_popup);
+        assert_eq!(rx.details.try_recv().unwrap().1, "b");
+        // Previewing comments marks the post read
+        assert!(app.posts[1].is_read);
+        assert!(matches!(
+            rx.db.try_recv().unwrap(),
+            DatabaseAction::MarkPostRead { .. }
+        ));
+
+        // The post shown in the popup stays selected
+        app.dispatch(Command::Select(0), &tx).unwrap();
+        app.dispatch(Command::NextRow, &tx).unwrap();
+        assert_eq!(selected_id(&app), "b");
+        // Moved from the post to its first comment
+        assert_eq!(app.comments_list_state.selected(), Some(0));
+
+        app.dispatch(Command::NextTag, &tx).unwrap();
+        assert_eq!(app.selected_tag, Some(0));
+
+        app.dispatch(Command::ClosePopups, &tx).unwrap();
+        assert!(!app.show_details_popup);
+    }
+
+    #[test]
+    fn mute_tag_without_fetching() {
+        let (mut app, tx, rx) = app();
+        app.posts[1].tags = vec!["go".to_string()];
+        let conn = get_db_connection(&app.db).unwrap();
+        let base_url = &app.instance().base_url;
+        store_listing(
+            &conn,
+            base_url,
+            &app.mode.path(),
+            &app.posts,
+            &Default::default(),
+        )
+        .unwrap();
+        drop(conn);
+
+        app.dispatch(Command::Select(1), &tx).unwrap();
+        app.dispatch(Command::ToggleDetails, &tx).unwrap();
+        app.dispatch(Command::NextTag, &tx).unwrap();
+        app.dispatch(Command::MuteTag, &tx).unwrap();
+        assert_eq!(app.posts.len(), 2);
+        assert_eq!(app.listing_counts().filtered, 1);
+
+        // Listed again from the stored listing
+        app.dispatch(Command::ToggleTagFilterAction, &tx).unwrap();
+        assert_eq!(app.posts.len(), 3);
+        assert!(app.posts[1].is_filtered);
+        assert!(rx.posts.try_recv().is_err());
+    }
+
+    #[test]
+    fn hide_and_undo() {
+        let (mut app, tx, rx) = app();
+
+        app.dispatch(Command::Select(1), &tx).unwrap();
+        app.dispatch(Command::Hide, &tx).unwrap();
+        assert_eq!(app.posts.len(), 2);
+        assert_eq!(selected_id(&app), "c");
+        assert_eq!(app.listing_counts().hidden, 1);
+        assert!(matches!(
+            rx.db.try_recv().unwrap(),
+            DatabaseAction::HidePost { id, .. } if id == "b"
+        ));
+
+        app.dispatch(Command::UndoHide, &tx).unwrap();
+        assert_eq!(app.posts.len(), 3);
+        assert_eq!(app.listing_counts().hidden, 0);
+        assert!(matches!(
+            rx.db.try_recv().unwrap(),
+            DatabaseAction::UnhidePost { id, .. } if id == "b"
+        ));
+
+        // Undone from another page
+        app.dispatch(Command::Hide, &tx).unwrap();
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        app.replace_posts(vec![test_post("d")]);
+        app.dispatch(Command::UndoHide, &tx).unwrap();
+        app.dispatch(Command::PreviousPage, &tx).unwrap();
+        assert_eq!(app.listing_counts().hidden, 0);
+
+        // Nothing left to select
+        for _ in 0..app.posts.len() {
+            app.dispatch(Command::Hide, &tx).unwrap();
+        }
+        assert!(app.posts.is_empty());
+        assert_eq!(app.posts_list_state.selected(), None);
+
+        // Still possible to move on from the emptied page
+        let page = app.mode.get_page();
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert_eq!(app.mode.get_page(), page + 1);
+    }
+
+    #[test]
+    fn empty_listing() {
+        let (mut app, tx, _rx) = app();
+        app.posts.clear();
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('E'), KeyModifiers::SHIFT), &tx)
+            .unwrap();
+        assert!(!app.show_filters_popup);
+        app.dispatch(Command::NextTag, &tx).unwrap();
+        app.dispatch(Command::MuteTag, &tx).unwrap();
+        assert_eq!(app.selected_tag, None);
+
+        app.show_details_popup = true;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+    }
+
+    #[test]
+    fn keys_and_mouse() {
+        let (mut app, tx, _rx) = app();
+        let mut press = |c| {
+            app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), &tx)
+                .unwrap();
+            (app.posts_list_state.selected(), app.show_keybinds_popup)
+        };
+
+        assert_eq!(press('j'), (Some(1), false));
+        assert_eq!(press('?'), (Some(1), true));
+        // Any other key closes the popup
+        assert_eq!(press('k'), (Some(0), false));
+        assert_eq!(press('?'), (Some(0), true));
+        assert_eq!(press('?'), (Some(0), false));
+
+        app.table_starts_at = 5;
+        app.table_ends_at = 10;
+        let mouse = |kind, row| MouseEvent {
+            kind,
+            column: 0,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        app.handle_mouse_event(mouse(MouseEventKind::Moved, 9), &tx)
+            .unwrap();
+        assert_eq!(selected_id(&app), "c");
+        app.handle_mouse_event(mouse(MouseEventKind::ScrollUp, 0), &tx)
+            .unwrap();
+        assert_eq!(selected_id(&app), "b");
+
+        // Hovering comments listed instead of posts
+        app.mode = Mode::Comments(1);
+        app.replace_comments(vec![test_comment("a", ""), test_comment("b", "")]);
+        app.posts.clear();
+        app.handle_mouse_event(
+            mouse(MouseEventKind::Moved, 5 + TABLE_ROW_HEIGHT as u16),
+            &tx,
+        )
+        .unwrap();
+        assert_eq!(app.posts_list_state.selected(), Some(1));
+
+        app.handle_key_event(
+            KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            &tx,
+        )
+        .unwrap();
+        assert!(!app.is_running.load(Ordering::Relaxed));
+        assert_eq!(app.exit_code, 1);
+    }
+
+    #[test]
+    fn user_of_post_or_comment() {
+        let (mut app, tx, rx) = app();
+        app.posts[0].comments = vec![test_comment("https://lobste.rs/s/a/post_a#c_b", "")];
+
+        // The post is selected in the details popup until moving down to its
+        // comments
+        app.dispatch(Command::ToggleDetails, &tx).unwrap();
+        assert_eq!(app.selected_user(), Some("user"));
+        app.dispatch(Command::NextRow, &tx).unwrap();
+        assert_eq!(app.selected_user(), Some("commenter"));
+        app.dispatch(Command::PreviousRow, &tx).unwrap();
+        assert_eq!(app.selected_user(), Some("user"));
+
+        app.dispatch(Command::UserThreads, &tx).unwrap();
+        let threads = Mode::UserThreads(String::from("user"), 1);
+        assert_eq!(app.mode, threads);
+        assert_eq!(rx.posts.try_recv().unwrap().1, threads);
+        app.replace_comments(vec![test_comment("https://lobste.rs/s/a/post_a#c_b", "")]);
+        assert_eq!(app.selected_user(), Some("commenter"));
+
+        // Comments listed are kept when switching back to them
+        app.dispatch(Command::SetMode(Mode::Hottest(1)), &tx)
+            .unwrap();
+        assert_eq!(app.list_len(), 3);
+        app.dispatch(Command::SetMode(threads), &tx).unwrap();
+        assert_eq!(app.list_len(), 1);
+        assert!(rx.posts.try_recv().is_err());
+    }
+
+    #[test]
+    fn switching_instance_clears_error() {
+        let (mut app, tx, rx) = app();
+        app.instances.push(Arc::new(Instance::new(
+            String::from("localhost"),
+            String::from("http://localhost:3000"),
+        )));
+        app.instance_states.push(None);
+        app.fetch_error = Some(String::from("failed requesting posts"));
+
+        app.dispatch(Command::NextInstance, &tx).unwrap();
+        assert_eq!(app.instance().name, "localhost");
+        assert_eq!(app.fetch_error, None);
+        assert_eq!(
+            rx.posts.try_recv().unwrap().0.base_url,
+            "http://localhost:3000"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::Utc;
+use ratatui::{Frame, layout::Rect, style::{Style, Stylize}, text::{Line, Span, Text}, widgets::{List, ListItem, ListState}};
+use textwrap::wrap;
+
+use crate::{config::Config, data::PostComment, utils::{human_duration, truncate}};
+
+/// Maximum number of lines of each comment to show when listing comments.
+const MAX_EXCERPT_LINES: u16 = 4;
+
+/// Format a comment as a line with the user, score and time since it was
+/// posted, followed by the comment wrapped to the given width, indented
+/// according to `depth`.
+pub fn comment_text(
+    comment: &PostComment,
+    config: &Config,
+    depth: usize,
+    max_width: usize,
+    max_lines: u16,
+) -> Text<'static> {
+    let (score_fg, score_icon) = if comment.score < 0 {
+        (
+            config.ui.score_count.fg_negative,
+            config.ui.score_count.icon_negative,
+        )
+    } else {
+        (
+            config.ui.score_count.fg_positive,
+            config.ui.score_count.icon_positive,
+        )
+    };
+    let since_comment = Utc::now().naive_utc() - comment.created_at.naive_utc();
+    let since_comment = human_duration(since_comment);
+
+    let indented = if depth == 0 {
+        String::new()
+    } else {
+        "  ".repeat(depth)
+    };
+    let max_width = max_width.saturating_sub(indented.len());
+
+    let first_line = Line::from_iter([
+        Span::from(indented.clone()),
+        Span::from(format!(
+            "{} {}  ",
+            config.ui.submitted_user.icon, comment.commenting_user
+        ))
+        .fg(config.ui.submitted_user.fg),
+        Span::from(format!("{} {}  ", score_icon, comment.score)).fg(score_fg),
+        Span::from(format!(
+            "{} {}",
+            config.ui.submitted_elapsed.icon, since_comment
+        ))
+        .fg(config.ui.submitted_elapsed.fg),
+    ]);
+
+    let mut text = Text::from(first_line);
+
+    for (lines, l) in (1..).zip(wrap(comment.comment_plain.trim(), max_width).iter()) {
+        if lines >= max_lines {
+            text.push_line(Line::from(format!("{indented}...")));
+            break;
+        }
+
+        text.push_line(format!("{indented}{}", l.trim().to_owned()));
+    }
+
+    text.push_line(Line::default());
+
+    text
+}
+
+/// Render a list of comments, for modes which list comments rather than posts.
+/// Each comment is preceded by the title of the post it was made on.
+pub fn render_comments(
+    frame: &mut Frame,
+    area: Rect,
+    list_state: &mut ListState,
+    comments: &[PostComment],
+    config: &Config,
+    base_url: &str,
+) {
+    let max_width = area.width as usize;
+
+    let items = comments.iter().map(|comment| {
+        let title = comment.story_title(base_url).unwrap_or_default();
+        let mut text = Text::from(Line::from(truncate(&title, max_width).into_owned()).bold());
+        text.extend(comment_text(
+            comment,
+            config,
+            0,
+            max_width,
+            MAX_EXCERPT_LINES,
+        ));
+
+        ListItem::from(text)
+    });
+
+    let list = List::from_iter(items)
+        .scroll_padding(1)
+        .highlight_style(Style::default().bold());
+
+    frame.render_stateful_widget(list, area, list_state);
+}
@@ -1,22 +1,41 @@
-use std::sync::atomic::{AtomicU32, Ordering};
-
 use ratatui::{Frame, layout::Rect, style::{Style, Stylize}, text::{Line, Span}, widgets::{Block, BorderType, Borders}};
 
-use crate::{config::{Config, DownloadedConfig, HeaderConfig, KeybindHintsConfig}, modes::Mode, utils::human_bytes};
+use crate::{app::ListingCounts, config::{Config, DownloadedConfig, HeaderConfig, KeyAction, KeybindHintsConfig, KeybindsConfig}, data::Bandwidth, modes::Mode, utils::human_bytes};
+
+fn downloaded(config: &DownloadedConfig, bandwidth: Bandwidth) -> Line<'_> {
+    let prefetched = bandwidth.prefetched;
+    let bytes = bandwidth.total() - prefetched;
+    let text = if prefetched > 0 {
+        format!(
+            " {} {} (+{} prefetched) ",
+            config.icon,
+            human_bytes(bytes),
+            human_bytes(prefetched)
+        )
+    } else {
+        format!(" {} {} ", config.icon, human_bytes(bytes))
+    };
 
-fn downloaded(config: &DownloadedConfig, bytes: u32) -> Line<'_> {
-    Line::from(format!(" {} {} ", config.icon, human_bytes(bytes)))
-        .right_aligned()
-        .fg(config.fg)
+    Line::from(text).right_aligned().fg(config.fg)
 }
 
-fn keybind_hints(config: &KeybindHintsConfig) -> Line<'_> {
+fn keybind_hints<'a>(config: &'a KeybindHintsConfig, keybinds: &KeybindsConfig) -> Line<'a> {
+    let key = |action| {
+        let key = keybinds.keys(action).first().map(|k| k.label());
+        format!(
+            "{}{}{}",
+            config.icon_left,
+            key.unwrap_or_default(),
+            config.icon_right
+        )
+    };
+
     Line::from(vec![
         Span::from(" "),
-        Span::from(format!("{}q{}", config.icon_left, config.icon_right)).bold(),
+        Span::from(key(KeyAction::Quit)).bold(),
         Span::from(" quit"),
         Span::from(" | "),
-        Span::from(format!("{}?{}", config.icon_left, config.icon_right)).bold(),
+        Span::from(key(KeyAction::ToggleKeybinds)).bold(),
         Span::from(" keybinds"),
         Span::from(" "),
     ])
@@ -37,13 +56,23 @@ pub fn render_container(
     area: Rect,
     config: &Config,
     mode: &Mode,
-    bytes_downloaded: &AtomicU32,
+    instance_name: Option<&str>,
+    downloaded_bytes: Bandwidth,
+    counts: ListingCounts,
 ) {
     let mut block = Block::new();
 
+    if let Some(name) = instance_name {
+        block = block.title_top(Line::from(format!(" {name} ")).left_aligned().bold());
+    }
+
+    if config.offline {
+        block = block.title_top(Line::from(" Offline ").left_aligned().bold().italic());
+    }
+
     if config.ui.mode_info.enable {
-        let (Mode::Newest(n) | Mode::Hottest(n) | Mode::Active(n)) = mode;
-        let mode_info = Line::default()
+        let page = mode.get_page() + 1;
+        let mut mode_info = Line::default()
             .left_aligned()
             .fg(config.ui.mode_info.fg)
             .spans([
@@ -51,8 +80,23 @@ pub fn render_container(
                 Span::from(mode.to_string()),
                 Span::from("  "),
                 Span::from("Page: ").bold(),
-                Span::from(format!("{n}  ")),
+                Span::from(format!("{page}  ")),
             ]);
+        if counts.hidden > 0 {
+            mode_info.push_span(Span::from("Hidden: ").bold());
+            mode_info.push_span(Span::from(format!("{}  ", counts.hidden)));
+        }
+        if counts.filtered > 0 {
+            mode_info.push_span(Span::from("Filtered: ").bold());
+            mode_info.push_span(Span::from(format!(
+                "{} ({})  ",
+                counts.filtered, config.tag_filter.action
+            )));
+        }
+        if counts.ruled_out > 0 {
+            mode_info.push_span(Span::from("Ruled out: ").bold());
+            mode_info.push_span(Span::from(format!("{}  ", counts.ruled_out)));
+        }
         block = block.title_bottom(mode_info);
     }
 
@@ -68,12 +112,11 @@ pub fn render_container(
     };
 
     if config.ui.downloaded.enable {
-        let bytes = bytes_downloaded.load(Ordering::Relaxed);
-        block = block.title_top(downloaded(&config.ui.downloaded, bytes));
+        block = block.title_top(downloaded(&config.ui.downloaded, downloaded_bytes));
     };
 
     if config.ui.keybind_hints.enable {
-        block = block.title_bottom(keybind_hints(&config.ui.keybind_hints));
+        block = block.title_bottom(keybind_hints(&config.ui.keybind_hints, &config.keybinds));
     }
 
     frame.render_widget(block, area);
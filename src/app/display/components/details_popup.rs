@@ -1,16 +1,18 @@
-use chrono::Utc;
-use ratatui::{Frame, layout::{Constraint::{Fill, Length, Percentage}, Layout, Rect}, style::{Style, Stylize}, text::{Line, Span, Text}, widgets::{Block, BorderType, Borders, Cell, Clear, HighlightSpacing, List, ListItem, ListState, Padding, Row, Table}};
-use textwrap::wrap;
+use ratatui::{Frame, layout::{Constraint::{Fill, Length, Percentage}, Layout, Rect}, style::{Style, Stylize}, text::{Line, Span}, widgets::{Block, BorderType, Borders, Cell, Clear, HighlightSpacing, List, ListItem, ListState, Padding, Row, Table}};
 
-use crate::{config::Config, data::Post, utils::{center_area, human_duration}};
+use super::{comment_text, loading_line};
+use crate::{config::Config, data::Post, utils::center_area};
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_details_popup(
     frame: &mut Frame,
     area: Rect,
     config: &Config,
     post: &Post,
     is_loading: bool,
+    attempts_left: Option<u32>,
     list_state: &mut ListState,
+    selected_tag: Option<usize>,
 ) {
     frame.render_widget(Clear, area);
 
@@ -29,7 +31,7 @@ pub fn render_details_popup(
 
     if is_loading {
         let area = center_area(area, Percentage(100), Length(1));
-        frame.render_widget(Line::from("Loading...").centered(), area);
+        frame.render_widget(loading_line(attempts_left), area);
         return;
     }
 
@@ -47,7 +49,10 @@ pub fn render_details_popup(
             Cell::from("Posted".bold()),
             Cell::from(post.created_at.naive_local().to_string()),
         ]),
-        Row::new([Cell::from("Tags".bold()), Cell::from(post.tags.join(", "))]),
+        Row::new([
+            Cell::from("Tags".bold()),
+            Cell::from(tags_line(config, &post.tags, selected_tag)),
+        ]),
         Row::new([Cell::from("ID".bold()), Cell::from(post.short_id.clone())]),
     ];
 
@@ -67,58 +72,13 @@ pub fn render_details_popup(
         .comments
         .iter()
         .map(|comment| {
-            let (score_fg, score_icon) = if comment.score < 0 {
-                (
-                    config.ui.score_count.fg_negative,
-                    config.ui.score_count.icon_negative,
-                )
-            } else {
-                (
-                    config.ui.score_count.fg_positive,
-                    config.ui.score_count.icon_positive,
-                )
-            };
-            let since_comment = Utc::now().naive_utc() - comment.created_at.naive_utc();
-            let since_comment = human_duration(since_comment);
-
-            let indented = if comment.depth == 0 {
-                String::new()
-            } else {
-                "  ".repeat(comment.depth)
-            };
-            let max_width = max_width.saturating_sub(indented.len());
-
-            let first_line = Line::from_iter([
-                Span::from(indented.clone()),
-                Span::from(format!(
-                    "{} {}  ",
-                    config.ui.submitted_user.icon, comment.commenting_user
-                ))
-                .fg(config.ui.submitted_user.fg),
-                Span::from(format!("{} {}  ", score_icon, comment.score)).fg(score_fg),
-                Span::from(format!(
-                    "{} {}",
-                    config.ui.submitted_elapsed.icon, since_comment
-                ))
-                .fg(config.ui.submitted_elapsed.fg),
-            ]);
-
-            let mut text = Text::from(first_line);
-
-            let mut lines = 1;
-            for l in wrap(comment.comment_plain.trim(), max_width).iter() {
-                if lines >= max_lines {
-                    text.push_line(Line::from(format!("{indented}...")));
-                    break;
-                }
-
-                text.push_line(format!("{indented}{}", l.trim().to_owned()));
-                lines += 1;
-            }
-
-            text.push_line(Line::default());
-
-            ListItem::from(text)
+            ListItem::from(comment_text(
+                comment,
+                config,
+                comment.depth,
+                max_width,
+                max_lines,
+            ))
         })
         .collect();
 
@@ -136,3 +96,30 @@ pub fn render_details_popup(
         );
     frame.render_stateful_widget(popup, comments, list_state);
 }
+
+/// Tags of the post, showing which are muted or the only ones shown, with the
+/// selected tag reversed.
+fn tags_line<'a>(config: &Config, tags: &'a [String], selected: Option<usize>) -> Line<'a> {
+    let filter = &config.tag_filter;
+    let mut spans = Vec::new();
+
+    for (i, tag) in tags.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::from(", "));
+        }
+
+        let mut span = Span::from(tag.as_str());
+        if filter.muted.contains(tag) {
+            span = span.crossed_out().dim();
+        }
+        if filter.only.contains(tag) {
+            span = span.underlined();
+        }
+        if selected == Some(i) {
+            span = span.reversed();
+        }
+        spans.push(span);
+    }
+
+    Line::from(spans)
+}
@@ -0,0 +1,34 @@
+use ratatui::{Frame, layout::{Constraint::{Fill, Length}, Layout, Margin, Rect}, style::{Style, Stylize}, text::Line, widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap}};
+use textwrap::wrap;
+
+use crate::config::Config;
+
+const PADDING: u16 = 1;
+
+/// Render a banner with the given error at the bottom of the given area.
+pub fn render_error_banner(frame: &mut Frame, area: Rect, config: &Config, error: &str) {
+    let area = area.inner(Margin {
+        vertical: 1,
+        horizontal: 2,
+    });
+    let max_width = area.width.saturating_sub(2 + PADDING * 2) as usize;
+    let lines = wrap(error, max_width).len() as u16;
+    let [_, area] = Layout::vertical([Fill(1), Length(lines + 2)]).areas(area);
+
+    frame.render_widget(Clear, area);
+
+    let block = Block::new()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(config.ui.error_banner.fg))
+        .padding(Padding::horizontal(PADDING))
+        .title_top(Line::from(" Error ").bold())
+        .title_bottom(Line::from(" R retry | Esc dismiss ").right_aligned());
+
+    let banner = Paragraph::new(error)
+        .wrap(Wrap { trim: true })
+        .fg(config.ui.error_banner.fg)
+        .block(block);
+
+    frame.render_widget(banner, area);
+}
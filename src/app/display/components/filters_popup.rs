@@ -0,0 +1,63 @@
+use ratatui::{Frame, layout::{Constraint::{Length, Percentage}, Rect}, style::{Style, Stylize}, text::Line, widgets::{Block, BorderType, Borders, Cell, Clear, Padding, Row, Table}};
+
+use crate::{config::Config, data::Post, utils::{center_area, truncate}};
+
+const PADDING: u16 = 2;
+const SPACING: u16 = 2;
+const WIDTH: u16 = 70;
+const WIDTH_MARKER: u16 = 7;
+
+/// Render a popup listing the filter rules (and the tag filter) along with
+/// whether they match the post, to see why it is filtered.
+pub fn render_filters_popup(frame: &mut Frame, area: Rect, config: &Config, post: &Post) {
+    let rules = &config.filters;
+    let height = rules.len().max(1) as u16 + 2 + 2 + PADDING * 2;
+
+    let area = center_area(area, Length(WIDTH), Length(height));
+    frame.render_widget(Clear, area);
+
+    let width_title = usize::from(WIDTH - 2 - PADDING * 2 * 2);
+    let mut block = Block::new()
+        .padding(Padding::proportional(PADDING))
+        .title_top(" Filters ".white())
+        .title_bottom(Line::from(format!(" {} ", truncate(&post.title, width_title))).centered());
+
+    if config.ui.borders.enable {
+        block = block
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(config.ui.borders.fg));
+    }
+
+    let marker = |matches: bool, applied: bool| match (matches, applied) {
+        (true, true) => Cell::from("applied").bold(),
+        (true, false) => Cell::from("matches"),
+        _ => Cell::from(""),
+    };
+
+    let mut rows = vec![
+        Row::new([
+            marker(post.is_filtered, post.is_filtered),
+            Cell::from(format!("{} by tags", config.tag_filter.action)),
+        ])
+        .bottom_margin(1),
+    ];
+    rows.extend(rules.iter().enumerate().map(|(i, rule)| {
+        Row::new([
+            marker(rule.matches(post), post.filter_rule == Some(i)),
+            Cell::from(rule.to_string()),
+        ])
+    }));
+    if rules.is_empty() {
+        rows.push(Row::new([
+            Cell::from(""),
+            Cell::from("No filter rules").italic(),
+        ]));
+    }
+
+    let popup = Table::new(rows, [Length(WIDTH_MARKER), Percentage(100)])
+        .block(block)
+        .column_spacing(SPACING);
+
+    frame.render_widget(popup, area);
+}
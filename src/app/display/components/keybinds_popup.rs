@@ -1,39 +1,45 @@
-use ratatui::{Frame, layout::{Constraint::{Fill, Length}, Rect}, style::{Style, Stylize}, widgets::{Block, BorderType, Borders, Cell, Clear, Padding, Row, Table}};
+use ratatui::{Frame, layout::{Constraint::{Fill, Length}, Rect}, style::{Style, Stylize}, text::Line, widgets::{Block, BorderType, Borders, Cell, Clear, Padding, Row, Table}};
 
 use crate::{config::Config, utils::center_area};
 
 const PADDING: u16 = 2;
 const SPACING: u16 = 2;
 
-const KEYS: [[&str; 2]; 17] = [
-    ["    󰁅 / j", "Scroll down"],
-    ["    󰁝 / k", "Scroll up"],
-    ["  G / End", "Scroll to last"],
-    [" g / Home", "Scroll to first"],
-    ["    󰁍 / h", "Previous page"],
-    ["    󰁔 / l", "Next page"],
-    ["  L / Tab", "Next mode"],
-    ["H / S+Tab", "Previous mode"],
-    ["    Enter", "Open post"],
-    ["        c", "Open comments"],
-    ["        r", "Mark read"],
-    ["        u", "Mark unread"],
-    ["        K", "Toggle details"],
-    ["   Escape", "Close popup"],
-    ["   R / F5", "Refresh"],
-    ["        q", "Quit"],
-    ["   Ctrl+c", "Force quit"],
-];
-const HEIGHT: u16 = KEYS.len() as u16 + PADDING * 2 + 2;
-
+/// Render a popup listing the keys bound to each action, followed by any keys
+/// the config took from the actions they are bound to by default.
 pub fn render_keybinds_popup(frame: &mut Frame, area: Rect, config: &Config) {
-    let width_key: u16 = KEYS[0][0].chars().count() as u16;
-    let width_desc = KEYS.iter().map(|[_, d]| d.len()).max().unwrap() as u16;
+    let keybinds = &config.keybinds;
+    let keys: Vec<(String, String)> = keybinds
+        .bindings()
+        .iter()
+        .filter(|(_, chords)| !chords.is_empty())
+        .map(|(action, chords)| {
+            let labels: Vec<String> = chords.iter().map(|c| c.label()).collect();
+            (labels.join(" / "), action.description().to_owned())
+        })
+        .chain(keybinds.overridden().iter().map(|(chord, action)| {
+            (
+                chord.label(),
+                format!(
+                    "No longer {} (rebound)",
+                    action.description().to_lowercase()
+                ),
+            )
+        }))
+        .collect();
+
+    let width_key = keys
+        .iter()
+        .map(|(k, _)| k.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let width_desc = keys.iter().map(|(_, d)| d.len()).max().unwrap_or(0) as u16;
     let width_borders = 2;
     let width_padding = PADDING * 2 * 2; // proportional padding
     let width = width_key + width_desc + width_borders + width_padding + SPACING;
+    let height = keys.len() as u16 + PADDING * 2 + 2;
 
-    let area = center_area(area, Length(width), Length(HEIGHT));
+    let area = center_area(area, Length(width), Length(height));
     frame.render_widget(Clear, area);
 
     let mut block = Block::new()
@@ -48,9 +54,14 @@ pub fn render_keybinds_popup(frame: &mut Frame, area: Rect, config: &Config) {
     }
 
     let widths = vec![Length(width_key), Fill(1)];
-    let rows: Vec<Row> = KEYS
+    let rows: Vec<Row> = keys
         .into_iter()
-        .map(|[keys, desc]| Row::new([Cell::from(keys).bold(), Cell::from(desc)]))
+        .map(|(keys, desc)| {
+            Row::new([
+                Cell::from(Line::from(keys).right_aligned()).bold(),
+                Cell::from(desc),
+            ])
+        })
         .collect();
 
     let popup = Table::new(rows, widths)
@@ -0,0 +1,12 @@
+use ratatui::text::Line;
+
+/// Placeholder shown while loading, including the number of attempts left if
+/// the request is being retried.
+pub fn loading_line(attempts_left: Option<u32>) -> Line<'static> {
+    match attempts_left {
+        Some(1) => Line::from("Loading... (retrying, last attempt)"),
+        Some(n) => Line::from(format!("Loading... (retrying, {n} attempts left)")),
+        None => Line::from("Loading..."),
+    }
+    .centered()
+}
@@ -1,13 +1,29 @@
+mod comments;
 mod container;
 mod details_popup;
+mod error_banner;
+mod filters_popup;
 mod header;
 mod keybinds_popup;
+mod loading;
+mod palette;
 mod posts;
 mod scrollbar;
+mod search_bar;
+mod search_seen;
+mod stats_popup;
 
+pub use comments::*;
 pub use container::*;
 pub use details_popup::*;
+pub use error_banner::*;
+pub use filters_popup::*;
 pub use header::*;
 pub use keybinds_popup::*;
+pub use loading::*;
+pub use palette::*;
 pub use posts::*;
 pub use scrollbar::*;
+pub use search_bar::*;
+pub use search_seen::*;
+pub use stats_popup::*;
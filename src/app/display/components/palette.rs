@@ -0,0 +1,78 @@
+use ratatui::{Frame, layout::{Constraint::{Fill, Length, Percentage}, Layout, Rect}, style::{Style, Stylize}, text::{Line, Span}, widgets::{Block, BorderType, Borders, Cell, Clear, Padding, Row, Table, TableState}};
+
+use crate::{app::PaletteEntry, config::Config, utils::center_area};
+
+const WIDTH: u16 = 76;
+const MAX_ENTRIES: u16 = 12;
+const WIDTH_NAME: u16 = 24;
+const WIDTH_KEYS: u16 = 12;
+
+/// Render the command palette, with the text typed into it and the commands
+/// matching it.
+pub fn render_palette(
+    frame: &mut Frame,
+    area: Rect,
+    config: &Config,
+    input: &str,
+    error: Option<&str>,
+    entries: &[PaletteEntry],
+    selected: usize,
+) {
+    let rows = (entries.len() as u16).clamp(1, MAX_ENTRIES);
+    // Input, separating line, entries and borders
+    let height = 1 + 1 + rows + 2;
+    let [area, _] = Layout::vertical([Length(height), Fill(1)])
+        .vertical_margin(3)
+        .areas(center_area(area, Length(WIDTH), Percentage(100)));
+    frame.render_widget(Clear, area);
+
+    let mut block = Block::new()
+        .padding(Padding::horizontal(1))
+        .title_top(" Commands ".white());
+
+    if config.ui.borders.enable {
+        block = block
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(config.ui.borders.fg));
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [input_area, list_area] = Layout::vertical([Length(1), Fill(1)])
+        .spacing(1)
+        .areas(inner);
+
+    let mut input_line =
+        Line::from_iter([Span::from(":").bold(), Span::from(input), Span::from("█")]);
+    if let Some(error) = error {
+        input_line.push_span(Span::from(format!("  {error}")).red());
+    }
+    frame.render_widget(input_line, input_area);
+
+    if entries.is_empty() {
+        frame.render_widget(Line::from("No matching commands").italic(), list_area);
+        return;
+    }
+
+    let rows = entries.iter().map(|entry| {
+        let name = match entry.arg {
+            Some(arg) => format!("{} {arg}", entry.name),
+            None => entry.name.clone(),
+        };
+
+        Row::new([
+            Cell::from(name).bold(),
+            Cell::from(entry.description),
+            Cell::from(Line::from(entry.keys.as_str()).right_aligned()).fg(config.ui.borders.fg),
+        ])
+    });
+
+    let table = Table::new(rows, [Length(WIDTH_NAME), Fill(1), Length(WIDTH_KEYS)])
+        .column_spacing(1)
+        .row_highlight_style(Style::default().reversed());
+    let mut state = TableState::default().with_selected(Some(selected));
+
+    frame.render_stateful_widget(table, list_area, &mut state);
+}
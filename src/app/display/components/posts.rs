@@ -1,17 +1,59 @@
 use chrono::Utc;
 use ratatui::{Frame, layout::Rect, style::{Color, Style, Stylize}, text::{Line, Span, Text}, widgets::{List, ListItem, ListState}};
 
-use crate::{app::handle_events::SHORTCUT_KEYS, config::Config, data::Post, utils::{human_duration, truncate}};
+use crate::{config::Config, data::Post, filters::FilterAction, utils::{human_duration, match_indices, truncate}};
 
 const COLUMN_SPACING: u16 = 1;
 const SHORTCUT_WIDTH: u16 = 5;
+pub(super) const SEARCH_MATCH: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
+
+/// Spans of the text in the given style, with the characters matching the
+/// query (if any) highlighted.
+fn highlight_matches(
+    text: &str,
+    query: Option<&str>,
+    fuzzy: bool,
+    style: Style,
+) -> Vec<Span<'static>> {
+    let indices = query
+        .and_then(|query| match_indices(query, text, fuzzy))
+        .unwrap_or_default();
+    let span = |text: String, is_match: bool| {
+        Span::styled(
+            text,
+            if is_match {
+                style.patch(SEARCH_MATCH)
+            } else {
+                style
+            },
+        )
+    };
+
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matches = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_match = indices.contains(&i);
+        if is_match != run_matches && !run.is_empty() {
+            spans.push(span(std::mem::take(&mut run), run_matches));
+        }
+        run_matches = is_match;
+        run.push(c);
+    }
+    spans.push(span(run, run_matches));
 
+    spans
+}
+
+/// Render the posts, highlighting where they match the query being searched
+/// for, if any.
 pub fn render_posts(
     frame: &mut Frame,
     area: Rect,
     list_state: &mut ListState,
     posts: &[Post],
     config: &Config,
+    search: Option<&str>,
 ) {
     let max_width = usize::from(
         area.width
@@ -26,20 +68,27 @@ pub fn render_posts(
 
     let rows = posts.iter().enumerate().map(|(i, post)| {
         let is_selected = selected == i;
-        let if_not_read = |c: Color| {
-            if post.is_read { Color::DarkGray } else { c }
+        // Read posts are dimmed, along with posts filtered out by their tags
+        // or by a rule (if not dropped)
+        let rule = post.filter_rule.and_then(|i| config.filters.get(i));
+        let is_dimmed =
+            post.is_read || post.is_filtered || rule.is_some_and(|r| r.action == FilterAction::Dim);
+        let if_not_dimmed = |c: Color| {
+            if is_dimmed { Color::DarkGray } else { c }
         };
 
         let since_post = Utc::now().naive_utc() - post.created_at.naive_utc();
 
-        let text_color = if is_selected {
-            if post.is_read {
-                Color::Gray
-            } else {
-                Color::White
+        let text_color = match rule {
+            Some(rule) if rule.action == FilterAction::Highlight => rule.fg,
+            _ if is_selected => {
+                if is_dimmed {
+                    Color::Gray
+                } else {
+                    Color::White
+                }
             }
-        } else {
-            if_not_read(Color::Gray)
+            _ => if_not_dimmed(Color::Gray),
         };
 
         let mut second_line = Line::default();
@@ -47,18 +96,22 @@ pub fn render_posts(
 
         let title = truncate(&post.title, max_width);
         if config.ui.shortcuts.enable {
+            // Posts past the keys left unbound get no shortcut
+            let key = config.keybinds.shortcut_keys().get(i).unwrap_or(&' ');
             let shortcut = format!(
                 "{}{}{}  ",
-                config.ui.shortcuts.icon_left,
-                SHORTCUT_KEYS[i] as char,
-                config.ui.shortcuts.icon_right
+                config.ui.shortcuts.icon_left, key, config.ui.shortcuts.icon_right
             );
             debug_assert_eq!(shortcut.chars().count() as u16, SHORTCUT_WIDTH);
 
-            first_line.push_span(Span::from(shortcut).fg(if_not_read(config.ui.shortcuts.fg)));
+            first_line.push_span(Span::from(shortcut).fg(if_not_dimmed(config.ui.shortcuts.fg)));
             second_line.push_span(Span::from("     "));
         }
-        first_line.push_span(Span::from(title).fg(text_color));
+        let mut title_style = Style::default().fg(text_color);
+        if post.is_hidden {
+            title_style = title_style.crossed_out();
+        }
+        first_line.extend(highlight_matches(&title, search, true, title_style));
 
         if config.ui.score_count.enable {
             let (fg, icon) = if post.score < 0 {
@@ -76,7 +129,7 @@ pub fn render_posts(
             second_line.push_span(
                 Span::from(format!("  {icon} {:<4}", post.score))
                     .bold()
-                    .fg(if_not_read(fg)),
+                    .fg(if_not_dimmed(fg)),
             );
         }
 
@@ -102,12 +155,22 @@ pub fn render_posts(
         }
 
         if config.ui.submitted_user.enable {
+            let style = Style::default().fg(config.ui.submitted_user.fg);
+            second_line.push_span(Span::styled(
+                format!("{} ", config.ui.submitted_user.icon),
+                style,
+            ));
+            second_line.extend(highlight_matches(
+                &post.submitter_user,
+                search,
+                false,
+                style,
+            ));
+        }
+
+        if config.ui.saved.enable && post.is_saved {
             second_line.push_span(
-                Span::from(format!(
-                    "{} {}",
-                    config.ui.submitted_user.icon, post.submitter_user
-                ))
-                .fg(config.ui.submitted_user.fg),
+                Span::from(format!("  {}", config.ui.saved.icon)).fg(config.ui.saved.fg),
             );
         }
 
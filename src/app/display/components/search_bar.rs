@@ -0,0 +1,66 @@
+use ratatui::{Frame, layout::{Constraint::{Fill, Length}, Layout, Margin, Rect}, style::{Style, Stylize}, text::{Line, Span}, widgets::{Block, BorderType, Borders, Clear, Padding}};
+
+use crate::config::{Config, KeyAction};
+
+/// Area the search bar is drawn over, at the bottom of the container.
+pub fn search_bar_area(area: Rect) -> Rect {
+    let area = area.inner(Margin {
+        vertical: 1,
+        horizontal: 2,
+    });
+    let [_, area] = Layout::vertical([Fill(1), Length(3)]).areas(area);
+
+    area
+}
+
+/// Render the query being searched for at the bottom of the given area, along
+/// with the number of rows matching it.
+pub fn render_search_bar(
+    frame: &mut Frame,
+    area: Rect,
+    config: &Config,
+    query: &str,
+    is_typing: bool,
+    matches: usize,
+) {
+    let area = search_bar_area(area);
+    frame.render_widget(Clear, area);
+
+    let key = |action| {
+        let keys = config.keybinds.keys(action);
+        keys.first().map(|k| k.label()).unwrap_or_default()
+    };
+    let hints = if is_typing {
+        String::from(" Enter done | Esc clear ")
+    } else {
+        format!(
+            " {} next | {} previous | {} clear ",
+            key(KeyAction::NextMatch),
+            key(KeyAction::PreviousMatch),
+            key(KeyAction::ClosePopup)
+        )
+    };
+
+    let block = Block::new()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(config.ui.borders.fg))
+        .padding(Padding::horizontal(1))
+        .title_top(Line::from(" Search ").bold())
+        .title_bottom(Line::from(hints).right_aligned());
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let mut input = Line::from_iter([Span::from("/").bold(), Span::from(query)]);
+    if is_typing {
+        input.push_span(Span::from("█"));
+    }
+    let matches = match matches {
+        1 => String::from("1 match"),
+        n => format!("{n} matches"),
+    };
+
+    frame.render_widget(input, inner);
+    frame.render_widget(Line::from(matches).right_aligned().italic(), inner);
+}
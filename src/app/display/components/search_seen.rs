@@ -0,0 +1,117 @@
+use chrono::Utc;
+use ratatui::{Frame, layout::{Constraint::{Fill, Length, Percentage}, Layout, Rect}, style::{Style, Stylize}, text::{Line, Span, Text}, widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Padding}};
+
+use super::posts::SEARCH_MATCH;
+use crate::{config::Config, database::queries::{SNIPPET_MATCH_END, SNIPPET_MATCH_START, SearchHit, Seen}, utils::{center_area, human_duration}};
+
+/// Split the snippet of a search hit into spans, highlighting the terms which
+/// matched.
+fn snippet_spans(snippet: &str) -> Vec<Span<'static>> {
+    let snippet = snippet.replace(['\n', '\r'], " ");
+    let mut spans = Vec::new();
+
+    for (i, part) in snippet.split(SNIPPET_MATCH_START).enumerate() {
+        // Only the first part starts outside of a match
+        let (matched, rest) = match part.split_once(SNIPPET_MATCH_END) {
+            Some((matched, rest)) if i > 0 => (matched, rest),
+            _ => ("", part),
+        };
+        if !matched.is_empty() {
+            spans.push(Span::styled(matched.to_owned(), SEARCH_MATCH));
+        }
+        if !rest.is_empty() {
+            spans.push(Span::from(rest.to_owned()));
+        }
+    }
+
+    spans
+}
+
+/// Format a search hit as a line with its kind, title, author and time since it
+/// was posted, followed by the snippet of where it matched.
+fn hit_text(hit: &SearchHit) -> Text<'static> {
+    let (kind, author, created_at) = match &hit.seen {
+        Seen::Post(post) => ("post", &post.submitter_user, post.created_at),
+        Seen::Comment(comment) => ("comment", &comment.commenting_user, comment.created_at),
+    };
+    let since = human_duration(Utc::now().naive_utc() - created_at.naive_utc());
+
+    let first_line = Line::from_iter([
+        Span::from(format!("{kind:<8}")).italic(),
+        Span::from(hit.title.clone()).bold(),
+        Span::from(format!("  {author}, {since}")).dim(),
+    ]);
+    let snippet = Line::from_iter(
+        [Span::from(" ".repeat(8))]
+            .into_iter()
+            .chain(snippet_spans(&hit.snippet)),
+    );
+
+    Text::from_iter([first_line, snippet])
+}
+
+/// Render the Search view, with the query typed into it and the posts and
+/// comments found, best match first.
+pub fn render_search_seen(
+    frame: &mut Frame,
+    area: Rect,
+    config: &Config,
+    query: &str,
+    hits: &[SearchHit],
+    list_state: &mut ListState,
+) {
+    let area = center_area(area, Percentage(90), Percentage(90));
+    frame.render_widget(Clear, area);
+
+    let mut block = Block::new()
+        .padding(Padding::horizontal(1))
+        .title_top(" Search everything seen ".white())
+        .title_bottom(Line::from(" Enter open | Esc close ").right_aligned());
+
+    if config.ui.borders.enable {
+        block = block
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(config.ui.borders.fg));
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [input_area, list_area] = Layout::vertical([Length(1), Fill(1)])
+        .spacing(1)
+        .areas(inner);
+
+    let input = Line::from_iter([Span::from("/").bold(), Span::from(query), Span::from("█")]);
+    frame.render_widget(input, input_area);
+
+    if hits.is_empty() {
+        let empty = if query.trim().is_empty() {
+            "Type to search the posts and comments seen"
+        } else {
+            "No results"
+        };
+        frame.render_widget(Line::from(empty).italic(), list_area);
+        return;
+    }
+
+    let items = hits.iter().map(|hit| ListItem::new(hit_text(hit)));
+    let list = List::new(items).highlight_style(Style::default().reversed());
+
+    frame.render_stateful_widget(list, list_area, list_state);
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_snippet_spans() {
+        let spans = snippet_spans("…using \u{2}io_uring\u{3} for\nfiles…");
+        let contents: Vec<_> = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(contents, ["…using ", "io_uring", " for files…"]);
+        assert_eq!(spans[1].style, SEARCH_MATCH);
+    }
+}
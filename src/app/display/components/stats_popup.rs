@@ -0,0 +1,69 @@
+use ratatui::{Frame, layout::{Constraint::Length, Rect}, style::{Style, Stylize}, widgets::{Block, BorderType, Borders, Cell, Clear, Padding, Row, Table}};
+
+use crate::{config::Config, data::{Bandwidth, BandwidthTotals}, utils::{center_area, human_bytes}};
+
+const PADDING: u16 = 2;
+const SPACING: u16 = 2;
+const WIDTH_LABEL: u16 = 10;
+const WIDTH_COLUMN: u16 = 8;
+
+const ROWS: u16 = 4;
+const HEIGHT: u16 = ROWS + 2 + PADDING * 2 + 2;
+
+/// Render a popup with the bytes downloaded from an instance in this session,
+/// today and across all sessions.
+pub fn render_stats_popup(
+    frame: &mut Frame,
+    area: Rect,
+    config: &Config,
+    session: Bandwidth,
+    totals: BandwidthTotals,
+) {
+    let width_borders = 2;
+    let width_padding = PADDING * 2 * 2; // proportional padding
+    let width = WIDTH_LABEL + (WIDTH_COLUMN + SPACING) * 3 + width_borders + width_padding;
+
+    let area = center_area(area, Length(width), Length(HEIGHT));
+    frame.render_widget(Clear, area);
+
+    let mut block = Block::new()
+        .padding(Padding::proportional(PADDING))
+        .title_top(" Downloaded ".white());
+
+    if config.ui.borders.enable {
+        block = block
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(config.ui.borders.fg));
+    }
+
+    let header = Row::new(["", "Session", "Today", "All time"].map(|h| Cell::from(h).bold()))
+        .bottom_margin(1);
+    let row = |label, bytes: fn(&Bandwidth) -> u64| {
+        Row::new([
+            Cell::from(label).bold(),
+            Cell::from(human_bytes(bytes(&session))),
+            Cell::from(human_bytes(bytes(&totals.today))),
+            Cell::from(human_bytes(bytes(&totals.all_time))),
+        ])
+    };
+    let rows = [
+        row("Listings", |b| b.listings),
+        row("Comments", |b| b.comments),
+        row("Prefetched", |b| b.prefetched),
+        row("Total", Bandwidth::total),
+    ];
+
+    let widths = [
+        Length(WIDTH_LABEL),
+        Length(WIDTH_COLUMN),
+        Length(WIDTH_COLUMN),
+        Length(WIDTH_COLUMN),
+    ];
+    let popup = Table::new(rows, widths)
+        .header(header)
+        .block(block)
+        .column_spacing(SPACING);
+
+    frame.render_widget(popup, area);
+}
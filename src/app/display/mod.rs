@@ -2,8 +2,8 @@ mod components;
 
 use std::sync::atomic::Ordering;
 
-use components::{render_container, render_details_popup, render_header, render_keybinds_popup, render_posts, render_scrollbar};
-use ratatui::{Frame, layout::{Constraint::{Length, Max, Min, Percentage}, Layout}, text::Line};
+use components::{loading_line, render_comments, render_container, render_details_popup, render_error_banner, render_filters_popup, render_header, render_keybinds_popup, render_palette, render_posts, render_scrollbar, render_search_bar, render_search_seen, render_stats_popup, search_bar_area};
+use ratatui::{Frame, layout::{Constraint::{Length, Max, Min, Percentage}, Layout, Rect}, text::Line};
 
 use super::App;
 use crate::utils::center_area;
@@ -20,13 +20,79 @@ impl App {
             return;
         };
 
-        let is_loading = self.is_loading.load(Ordering::Relaxed);
-
         let area = center_area(area, Max(100), Percentage(100));
         let [area] = Layout::vertical([Percentage(100)]).areas(area);
-        render_container(frame, area, &self.config, &self.mode, &self.downloaded);
+        // Only show the instance name if there are others to switch to
+        let instance = self.instance();
+        let instance_name = (self.instances.len() > 1).then_some(instance.name.as_str());
+        render_container(
+            frame,
+            area,
+            &self.config,
+            &self.mode,
+            instance_name,
+            instance.downloaded.load(),
+            self.listing_counts(),
+        );
         render_scrollbar(frame, area, &mut self.posts_scroll_state, &self.config);
 
+        self.draw_body(frame, area);
+
+        if self.show_filters_popup
+            && let Some(post) = self.current_post()
+        {
+            render_filters_popup(frame, area, &self.config, post);
+        }
+
+        if let Some(totals) = self.stats_popup {
+            let session = self.instance().downloaded.load();
+            render_stats_popup(frame, area, &self.config, session, totals);
+        }
+
+        if let Some(search) = &self.search {
+            render_search_bar(
+                frame,
+                area,
+                &self.config,
+                &search.query,
+                search.is_typing,
+                self.search_matches(),
+            );
+        }
+
+        if let Some(search) = &mut self.search_seen {
+            render_search_seen(
+                frame,
+                area,
+                &self.config,
+                &search.query,
+                &search.hits,
+                &mut search.list_state,
+            );
+        }
+
+        if let Some(palette) = &self.palette {
+            render_palette(
+                frame,
+                area,
+                &self.config,
+                &palette.input,
+                palette.error.as_deref(),
+                &self.palette_entries(),
+                palette.selected,
+            );
+        }
+
+        if let Some(error) = &self.fetch_error {
+            render_error_banner(frame, area, &self.config, error);
+        }
+    }
+
+    /// Draw everything within the container - the header, the list of posts (or
+    /// comments) and any popups.
+    fn draw_body(&mut self, frame: &mut Frame, area: Rect) {
+        let is_loading = self.is_loading.load(Ordering::Relaxed);
+
         let body = if self.config.ui.header.enable_ascii_header {
             let header_lines = self.config.ui.header.text_ascii_header.lines().count() as u16;
             let [header, body] = Layout::vertical([Min(header_lines), Percentage(100)])
@@ -43,17 +109,34 @@ impl App {
                 .areas::<1>(area)[0]
         };
 
-        let body = center_area(body, Max(60), Max((self.posts.len() * 2).max(3) as u16));
+        // Keep the list clear of the search bar
+        let body = if self.search.is_some() {
+            let bar = search_bar_area(area);
+            Rect {
+                height: body.height.min(bar.top().saturating_sub(body.top())),
+                ..body
+            }
+        } else {
+            body
+        };
+
+        let lists_comments = self.mode.lists_comments();
+        let body_height = if lists_comments {
+            body.height
+        } else {
+            (self.posts.len() * 2).max(3) as u16
+        };
+        let body = center_area(body, Max(60), Max(body_height));
 
         // Loading - return early
         if is_loading {
             let body = center_area(body, Percentage(100), Length(1));
-            frame.render_widget(Line::from("Loading...").centered(), body);
+            frame.render_widget(loading_line(self.posts_attempts_left), body);
             return;
         }
 
         // No posts - return early
-        if self.posts.is_empty() {
+        if self.list_len() == 0 {
             let body = center_area(body, Percentage(100), Length(1));
             frame.render_widget(Line::from("No Results").centered(), body);
             return;
@@ -63,26 +146,35 @@ impl App {
         self.table_starts_at = body.top();
         self.table_ends_at = body.bottom() - 2;
 
-        render_posts(
-            frame,
-            body,
-            &mut self.posts_list_state,
-            &self.posts,
-            &self.config,
-        );
+        if lists_comments {
+            render_comments(
+                frame,
+                body,
+                &mut self.posts_list_state,
+                &self.comments,
+                &self.config,
+                &self.instances[self.instance].base_url,
+            );
+        } else {
+            render_posts(
+                frame,
+                body,
+                &mut self.posts_list_state,
+                &self.posts,
+                &self.config,
+                self.search.as_ref().and_then(|s| s.posts_query()),
+            );
+        }
 
         if self.show_keybinds_popup {
             render_keybinds_popup(frame, area, &self.config);
         } else if self.show_details_popup
-            && let Some(index) = self.posts_list_state.selected()
+            && let Some(post) = self
+                .posts_list_state
+                .selected()
+                .and_then(|i| self.posts.get(i))
         {
-            assert!(
-                index < self.posts.len(),
-                "trying to open post out of bounds"
-            );
-
             let area = center_area(area, Percentage(90), Percentage(90));
-            let post = &self.posts[index];
             let is_loading = self.is_loading_comments.load(Ordering::Relaxed);
 
             render_details_popup(
@@ -91,7 +183,9 @@ impl App {
                 &self.config,
                 post,
                 is_loading,
+                self.details_attempts_left,
                 &mut self.comments_list_state,
+                self.selected_tag,
             );
         };
     }
@@ -1,10 +1,109 @@
 //! Cache-related functionality - store posts to and load posts from the caches.
-use std::mem;
+use std::{mem, sync::{Arc, atomic::Ordering, mpsc::Sender}};
 
-use super::App;
+use chrono::Utc;
+use color_eyre::{Result, eyre::{Context, eyre}};
+use rusqlite::Connection;
+use serde::{Serialize, de::DeserializeOwned};
+
+use super::{App, Instance, action::{PostsRequest, Purpose}, handle_posts::Fetched};
+use crate::{data::{Post, PostComment, PostDetails, Validators}, database::{get_db_connection, queries::{add_bandwidth, get_listing, get_listing_validators, get_post_details, get_post_details_validators, store_listing, store_post_details, touch_listing, touch_post_details, update_comments, update_posts}}, modes::Mode};
+
+/// Get the last results fetched for the mode's listing, for browsing offline.
+fn cached_listing<T: DeserializeOwned>(
+    conn: &Connection,
+    instance: &Instance,
+    mode: &Mode,
+) -> Result<T> {
+    get_listing(conn, &instance.base_url, &mode.path())?
+        .map(|cached| cached.data)
+        .ok_or_else(|| {
+            eyre!(
+                "page {} of {mode} was not loaded in a previous session, so is not available offline",
+                mode.get_page() + 1
+            )
+        })
+}
+
+/// Get the last details fetched for the post, for browsing offline.
+fn cached_post_details(
+    conn: &Connection,
+    instance: &Instance,
+    short_id: &str,
+) -> Result<PostDetails> {
+    get_post_details(conn, &instance.base_url, short_id)?
+        .map(|cached| cached.data)
+        .ok_or_else(|| {
+            eyre!("comments were not loaded in a previous session, so are not available offline")
+        })
+}
+
+/// Load a listing, only fetching it if it has changed since it was last
+/// fetched. Otherwise (or when offline), it is loaded from the cache.
+pub(super) fn load_listing<T: Serialize + DeserializeOwned>(
+    conn: &Connection,
+    instance: &Instance,
+    mode: &Mode,
+    offline: bool,
+    fetch: impl FnOnce(Option<&Validators>) -> Result<Fetched<T>>,
+) -> Result<T> {
+    if offline {
+        return cached_listing(conn, instance, mode);
+    }
+
+    let path = mode.path();
+    let validators = get_listing_validators(conn, &instance.base_url, &path)?;
+    match fetch(validators.as_ref())? {
+        Fetched::Modified(data, validators, bandwidth) => {
+            add_bandwidth(conn, &instance.base_url, &bandwidth)?;
+            store_listing(conn, &instance.base_url, &path, &data, &validators)?;
+            Ok(data)
+        }
+        Fetched::NotModified => {
+            touch_listing(conn, &instance.base_url, &path)?;
+            cached_listing(conn, instance, mode)
+        }
+    }
+}
+
+/// Load the details for a post, only fetching them if they have changed since
+/// they were last fetched. Otherwise (or when offline), they are loaded from
+/// the cache.
+pub(super) fn load_post_details(
+    conn: &Connection,
+    instance: &Instance,
+    short_id: &str,
+    offline: bool,
+    fetch: impl FnOnce(Option<&Validators>) -> Result<Fetched<PostDetails>>,
+) -> Result<PostDetails> {
+    if offline {
+        return cached_post_details(conn, instance, short_id);
+    }
+
+    let validators = get_post_details_validators(conn, &instance.base_url, short_id)?;
+    match fetch(validators.as_ref())? {
+        Fetched::Modified(details, validators, bandwidth) => {
+            add_bandwidth(conn, &instance.base_url, &bandwidth)?;
+            store_post_details(conn, &instance.base_url, &details, &validators)?;
+            Ok(details)
+        }
+        Fetched::NotModified => {
+            touch_post_details(conn, &instance.base_url, short_id)?;
+            cached_post_details(conn, instance, short_id)
+        }
+    }
+}
 
 impl App {
     pub(super) fn load_page(&mut self, prev_page: usize, page: usize) -> bool {
+        if self.mode.lists_comments() {
+            let mut prev_mode = self.mode.clone();
+            prev_mode.set_page(prev_page as u8 + 1);
+            self.cache_comments
+                .insert(prev_mode.path(), mem::take(&mut self.comments));
+            return self.load_cached_comments();
+        }
+
         if self.cache_posts.len() <= page {
             self.cache_posts.extend(
                 [Vec::new()]
@@ -20,7 +119,14 @@ impl App {
         !self.posts.is_empty()
     }
 
+    /// Store the current mode's pages in the cache. Modes are keyed by their
+    /// display name, so e.g. each domain gets its own entry.
     pub(super) fn store_mode(&mut self) {
+        if self.mode.lists_comments() {
+            self.cache_comments
+                .insert(self.mode.path(), mem::take(&mut self.comments));
+        }
+
         let page = self.mode.get_page();
         if page < self.cache_posts.len() {
             mem::swap(&mut self.posts, &mut self.cache_posts[page]);
@@ -28,14 +134,58 @@ impl App {
             self.cache_posts.push(mem::take(&mut self.posts));
         }
 
-        self.cache_modes
-            .insert(self.mode.to_string(), mem::take(&mut self.cache_posts));
+        self.cache_modes.insert(
+            self.mode.to_string(),
+            (self.mode.clone(), mem::take(&mut self.cache_posts)),
+        );
+    }
+
+    /// Whether the given page of the current mode has been loaded.
+    pub(super) fn is_page_cached(&self, page: usize) -> bool {
+        self.cache_posts.get(page).is_some_and(|p| !p.is_empty())
+    }
+
+    /// Store posts loaded for a mode or page other than the one being browsed,
+    /// e.g. if it was switched away from while loading.
+    pub(super) fn store_page(&mut self, mode: &Mode, posts: Vec<Post>) {
+        let pages = if mode.to_string() == self.mode.to_string() {
+            &mut self.cache_posts
+        } else {
+            &mut self
+                .cache_modes
+                .entry(mode.to_string())
+                .or_insert_with(|| (mode.clone(), Vec::new()))
+                .1
+        };
+
+        let page = mode.get_page();
+        if pages.len() <= page {
+            pages.resize_with(page + 1, Vec::new);
+        }
+        pages[page] = posts;
+    }
+
+    /// List the comments stored for the current mode's listing, if any.
+    fn load_cached_comments(&mut self) -> bool {
+        self.comments = self
+            .cache_comments
+            .remove(&self.mode.path())
+            .unwrap_or_default();
+
+        !self.comments.is_empty()
     }
 
     pub(super) fn load_mode(&mut self) -> bool {
+        if self.mode.lists_comments() {
+            return self.load_cached_comments();
+        }
+
         let page = self.mode.get_page();
         match self.cache_modes.get_mut(&self.mode.to_string()) {
-            Some(v) => {
+            Some((_, v)) => {
+                if v.len() <= page {
+                    v.resize_with(page + 1, Vec::new);
+                }
                 mem::swap(&mut self.cache_posts, v);
                 mem::swap(&mut self.posts, &mut self.cache_posts[page]);
             }
@@ -44,4 +194,121 @@ impl App {
 
         !self.posts.is_empty()
     }
+
+    /// Show the last results fetched for the current mode's listing, if it was
+    /// loaded in a previous session.
+    pub(super) fn load_cached_listing(&mut self) -> Result<bool> {
+        let conn = get_db_connection(&self.db)?;
+        let instance = Arc::clone(self.instance());
+
+        let fetched_at = if self.mode.lists_comments() {
+            let Some(mut cached) =
+                get_listing::<Vec<PostComment>>(&conn, &instance.base_url, &self.mode.path())?
+            else {
+                return Ok(false);
+            };
+            update_comments(&conn, &instance.base_url, &mut cached.data)?;
+            self.comments = cached.data;
+            cached.fetched_at
+        } else {
+            let Some(mut cached) =
+                get_listing::<Vec<Post>>(&conn, &instance.base_url, &self.mode.path())?
+            else {
+                return Ok(false);
+            };
+            let show_hidden = self.show_hidden.load(Ordering::Relaxed);
+            let hidden = update_posts(&conn, &instance.base_url, &mut cached.data, show_hidden)?;
+            self.filter_posts(&self.mode.clone(), &mut cached.data, hidden);
+            self.posts = cached.data;
+            cached.fetched_at
+        };
+
+        self.refreshed_at.insert(self.mode.path(), fetched_at);
+        self.is_loading.store(false, Ordering::Relaxed);
+        Ok(true)
+    }
+
+    /// Load the page being browsed again, e.g. after changing which posts are
+    /// listed. Other pages (for every instance) are loaded again when next
+    /// browsed.
+    pub(super) fn reload_posts(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        self.cache_posts.clear();
+        self.cache_modes.clear();
+        for state in self.instance_states.iter_mut().flatten() {
+            state.clear_posts();
+        }
+
+        self.refresh_posts(tx)
+    }
+
+    /// Quietly refresh the results being browsed in the background, if they
+    /// have expired.
+    pub(super) fn refresh_if_expired(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        if self.config.offline || self.is_loading.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let Some(ttl) = self.config.cache_ttl.ttl(&self.mode) else {
+            return Ok(());
+        };
+        let path = self.mode.path();
+        // Not loaded yet - nothing to refresh
+        let Some(refreshed_at) = self.refreshed_at.get(&path) else {
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        if now - *refreshed_at < ttl {
+            return Ok(());
+        }
+
+        // Don't request another refresh until the TTL has passed again, even if
+        // this one fails
+        self.refreshed_at.insert(path, now);
+        tx.send((
+            Arc::clone(self.instance()),
+            self.mode.clone(),
+            Purpose::Refresh,
+        ))
+        .context("load posts channel is closed")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeDelta;
+
+    use super::*;
+    use crate::app::command::test::app;
+
+    #[test]
+    fn refresh_if_expired() {
+        let (mut app, tx, rx) = app();
+        let path = app.mode.path();
+
+        // Not loaded yet
+        app.refresh_if_expired(&tx.posts).unwrap();
+        assert!(rx.posts.try_recv().is_err());
+
+        app.refreshed_at.insert(path.clone(), Utc::now());
+        app.refresh_if_expired(&tx.posts).unwrap();
+        assert!(rx.posts.try_recv().is_err());
+
+        app.refreshed_at
+            .insert(path.clone(), Utc::now() - TimeDelta::hours(1));
+        app.refresh_if_expired(&tx.posts).unwrap();
+        let (_, mode, purpose) = rx.posts.try_recv().unwrap();
+        assert_eq!(mode, app.mode);
+        assert_eq!(purpose, Purpose::Refresh);
+
+        // Not again until the TTL has passed again
+        app.refresh_if_expired(&tx.posts).unwrap();
+        assert!(rx.posts.try_recv().is_err());
+
+        app.refreshed_at
+            .insert(path, Utc::now() - TimeDelta::hours(1));
+        app.config.offline = true;
+        app.refresh_if_expired(&tx.posts).unwrap();
+        assert!(rx.posts.try_recv().is_err());
+    }
 }
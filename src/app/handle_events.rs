@@ -1,154 +1,56 @@
-use std::sync::{atomic::Ordering, mpsc::Sender};
-
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
-use super::{App, TABLE_ROW_HEIGHT, action::DatabaseAction};
-use crate::modes::Mode;
-
-// TODO: support custom keybinds?
-//
-// pub fn get_shortcut_keys(count: usize, used_keys: &[u8]) -> Vec<u8> {
-//     let mut res = Vec::with_capacity(count);
-//
-//     for k in ALL_KEYS {
-//         if used_keys.contains(k) {
-//             continue;
-//         }
-//
-//         res.push(*k);
-//         if res.len() == count {
-//             break;
-//         }
-//     }
-//
-//     res
-// }
-
-pub const SHORTCUT_KEYS: &[u8] = b"123456789abdefimnopstvwxyz;:$&*/|\\^`'\"[]()<>";
+use super::{App, TABLE_ROW_HEIGHT, command::{Channels, Command}};
+use crate::{data::BandwidthTotals, database::{get_db_connection, queries::{get_bandwidth_all_time, get_bandwidth_today}}};
 
 impl App {
-    pub(super) fn handle_key_event(
-        &mut self,
-        ev: KeyEvent,
-        tx_posts: &Sender<Mode>,
-        tx_details: &Sender<String>,
-        tx_db: &Sender<DatabaseAction>,
-    ) -> Result<()> {
-        // Ctrl+c
-        if ev.modifiers.contains(KeyModifiers::CONTROL) && matches!(ev.code, KeyCode::Char('c')) {
-            self.exit_code = 1;
-            self.is_running.store(false, Ordering::Release);
+    pub(super) fn handle_key_event(&mut self, ev: KeyEvent, tx: &Channels) -> Result<()> {
+        if self.palette.is_some() {
+            return self.handle_palette_key(ev, tx);
+        }
+        if self.search_seen.is_some() {
+            return self.handle_search_seen_key(ev, &tx.db);
+        }
+        if self.search.as_ref().is_some_and(|s| s.is_typing) {
+            self.handle_search_key(ev);
+            return Ok(());
         }
 
-        match ev.code {
-            KeyCode::Char('q') => self.is_running.store(false, Ordering::Release),
+        let command = match self.config.keybinds.action(&ev, self.show_details_popup) {
+            Some(action) => Some(Command::from(action)),
+            // Keys which aren't bound open the post they are shown next to
+            None => match ev.code {
+                KeyCode::Char(c) if (ev.modifiers - KeyModifiers::SHIFT).is_empty() => self
+                    .config
+                    .keybinds
+                    .shortcut_keys()
+                    .iter()
+                    .position(|k| *k == c)
+                    .filter(|i| *i < self.posts.len())
+                    .map(Command::OpenPost),
+                _ => None,
+            },
+        };
 
-            // POPUPS
-            KeyCode::Esc => {
-                self.show_details_popup = false;
-                self.show_keybinds_popup = false;
-            }
-            KeyCode::Char('?') => {
-                self.show_keybinds_popup = !self.show_keybinds_popup;
+        if let Some(command) = command {
+            let toggles_popup = command.toggles_popup();
+            self.dispatch(command, tx)?;
+            if toggles_popup {
                 return Ok(());
             }
-            KeyCode::Char('K') => {
-                if let Some(index) = self.posts_list_state.selected()
-                    && !self.posts.is_empty()
-                {
-                    self.show_details_popup = !self.show_details_popup;
-                    if self.show_details_popup {
-                        self.load_post_comments(index, tx_details, tx_db)?;
-                        self.comments_list_state.select(Some(0));
-                    }
-                };
-            }
-
-            // FUNCTIONALITY
-            KeyCode::Enter => {
-                if self.show_details_popup {
-                    self.open_comment()?;
-                } else if let Some(selected) = self.posts_list_state.selected() {
-                    self.open_post(selected, tx_db)?;
-                }
-            }
-            KeyCode::Char('c') => {
-                if let Some(selected) = self.posts_list_state.selected() {
-                    self.open_post_comments(selected, tx_db)?;
-                }
-            }
-
-            KeyCode::Char('r') => {
-                if let Some(selected) = self.posts_list_state.selected() {
-                    self.mark_post_read(selected, tx_db)?;
-                }
-            }
-            KeyCode::Char('u') => {
-                if let Some(selected) = self.posts_list_state.selected() {
-                    self.mark_post_unread(selected, tx_db)?;
-                }
-            }
-
-            KeyCode::Char('R') | KeyCode::F(5) => {
-                if self.show_details_popup {
-                    let index = self
-                        .posts_list_state
-                        .selected()
-                        .expect("in details popup - relevant post should be selected");
-                    self.load_post_comments(index, tx_details, tx_db)?;
-                } else {
-                    self.load_posts(tx_posts)?;
-                }
-            }
-
-            // NAVIGATION
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.next_row();
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.previous_row();
-            }
-
-            KeyCode::Char('h') | KeyCode::Left | KeyCode::PageUp => {
-                if !self.show_details_popup {
-                    self.previous_page(tx_posts)?
-                }
-            }
-            KeyCode::Char('l') | KeyCode::Right | KeyCode::PageDown => {
-                if !self.show_details_popup {
-                    self.next_page(tx_posts)?
-                }
-            }
-
-            KeyCode::Char('g') | KeyCode::Home => self.first_row(),
-            KeyCode::Char('G') | KeyCode::End => self.last_row(),
-
-            KeyCode::Char('H') | KeyCode::Tab => self.next_mode(tx_posts)?,
-            KeyCode::Char('L') | KeyCode::BackTab => self.prev_mode(tx_posts)?,
-
-            KeyCode::Char(c) => self.handle_shortcut_key(c, tx_db)?,
-
-            // For testing
-            // KeyCode::Char('p') => panic!("User triggered panic"),
-            // KeyCode::Char('e') => bail!("User triggered error"),
-            _ => {}
-        };
+        }
 
-        // Close popup when any (other) keybind is pressed
+        // Close popups when any (other) keybind is pressed
         self.show_keybinds_popup = false;
+        self.stats_popup = None;
+        self.show_filters_popup = false;
 
         Ok(())
     }
 
-    pub(super) fn handle_mouse_event(
-        &mut self,
-        ev: MouseEvent,
-        tx_posts: &Sender<Mode>,
-        tx_details: &Sender<String>,
-        tx_db: &Sender<DatabaseAction>,
-    ) -> Result<()> {
-        let max = self.posts.len();
+    pub(super) fn handle_mouse_event(&mut self, ev: MouseEvent, tx: &Channels) -> Result<()> {
+        let max = self.list_len();
         let get_hovered = || {
             let start = self.table_starts_at;
             let offset = self.posts_list_state.offset();
@@ -161,59 +63,41 @@ impl App {
             None
         };
 
-        match ev.kind {
-            MouseEventKind::ScrollDown => self.next_row(),
-            MouseEventKind::ScrollUp => self.previous_row(),
-            MouseEventKind::ScrollLeft => self.previous_page(tx_posts)?,
-            MouseEventKind::ScrollRight => self.next_page(tx_posts)?,
-            MouseEventKind::Moved => {
-                if !self.show_details_popup
-                    && let Some(i) = get_hovered()
-                {
-                    self.posts_list_state.select(Some(i));
-                }
-            }
-            MouseEventKind::Down(button) => {
-                if let Some(i) = get_hovered() {
-                    match button {
-                        MouseButton::Left => {
-                            if self.show_details_popup {
-                                self.open_comment()?;
-                            } else {
-                                self.open_post(i, tx_db)?;
-                            }
-                        }
-                        MouseButton::Right => {
-                            if let Some(index) = self.posts_list_state.selected()
-                                && !self.posts.is_empty()
-                            {
-                                self.show_details_popup = !self.show_details_popup;
-                                if self.show_details_popup {
-                                    self.load_post_comments(index, tx_details, tx_db)?;
-                                    self.comments_list_state.select(Some(0));
-                                }
-                            };
-                        }
-                        MouseButton::Middle => self.open_post_comments(i, tx_db)?,
-                    };
-                }
-            }
-            _ => {}
+        let commands: &[Command] = match (ev.kind, get_hovered()) {
+            (MouseEventKind::ScrollDown, _) => &[Command::NextRow],
+            (MouseEventKind::ScrollUp, _) => &[Command::PreviousRow],
+            (MouseEventKind::ScrollLeft, _) => &[Command::PreviousPage],
+            (MouseEventKind::ScrollRight, _) => &[Command::NextPage],
+            (MouseEventKind::Moved, Some(i)) => &[Command::Select(i)],
+            (MouseEventKind::Down(button), Some(i)) => match button {
+                MouseButton::Left => &[Command::Select(i), Command::Open],
+                MouseButton::Right => &[Command::ToggleDetails],
+                MouseButton::Middle => &[Command::Select(i), Command::OpenComments],
+            },
+            _ => &[],
+        };
+
+        for command in commands {
+            self.dispatch(command.clone(), tx)?;
         }
 
         Ok(())
     }
 
-    fn handle_shortcut_key(&mut self, c: char, tx_db: &Sender<DatabaseAction>) -> Result<()> {
-        if let Some((index, _)) = SHORTCUT_KEYS
-            .iter()
-            .enumerate()
-            .find(|(_, cc)| c == **cc as char)
-            && index < self.posts.len()
-        {
-            self.open_post(index, tx_db)?;
+    /// Show the bytes downloaded from the current instance in this session,
+    /// today and across all sessions.
+    pub(super) fn toggle_stats_popup(&mut self) -> Result<()> {
+        if self.stats_popup.take().is_some() {
+            return Ok(());
         }
 
+        let conn = get_db_connection(&self.db)?;
+        let base_url = &self.instance().base_url;
+        self.stats_popup = Some(BandwidthTotals {
+            today: get_bandwidth_today(&conn, base_url)?,
+            all_time: get_bandwidth_all_time(&conn, base_url)?,
+        });
+
         Ok(())
     }
 }
@@ -0,0 +1,193 @@
+//! Filters - filter posts out of listings by their tags (changing which tags
+//! are filtered while running), and apply the configured filter rules.
+use std::{mem, sync::atomic::Ordering};
+
+use color_eyre::Result;
+use rusqlite::Connection;
+
+use super::{App, ListingCounts};
+use crate::{config::TagFilterAction, data::Post, database::{get_db_connection, queries::{get_listing, update_posts}}, filters::{FilterAction, matching_rule}, modes::Mode};
+
+impl App {
+    /// Numbers of posts hidden or filtered out in the results for the page
+    /// being browsed.
+    pub(super) fn listing_counts(&self) -> ListingCounts {
+        self.listing_counts
+            .get(&self.mode.path())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Filter the posts loaded for the mode by their tags and the filter
+    /// rules, dropping, dimming or highlighting them, and record how many were
+    /// hidden (by the given count), filtered out or dropped. Posts stored
+    /// locally are never dropped.
+    pub(super) fn filter_posts(&mut self, mode: &Mode, posts: &mut Vec<Post>, hidden: usize) {
+        let filter = &self.config.tag_filter;
+        let rules = &self.config.filters;
+
+        for post in posts.iter_mut() {
+            post.filter_rule = matching_rule(rules, post);
+        }
+        let is_ruled_out = |p: &Post| {
+            p.filter_rule
+                .is_some_and(|i| rules[i].action == FilterAction::Hide)
+        };
+
+        let mut filtered = 0;
+        let mut ruled_out = 0;
+        if !mode.is_local() {
+            for post in posts.iter_mut() {
+                post.is_filtered = filter.filters(&post.tags);
+                filtered += usize::from(post.is_filtered);
+                ruled_out += usize::from(is_ruled_out(post));
+            }
+            let drop_filtered = filter.action == TagFilterAction::Hide;
+            posts.retain(|p| !(is_ruled_out(p) || drop_filtered && p.is_filtered));
+        }
+
+        self.listing_counts.insert(
+            mode.path(),
+            ListingCounts {
+                hidden,
+                filtered,
+                ruled_out,
+            },
+        );
+    }
+
+    /// Filter a page of posts loaded for the mode again, starting from its
+    /// stored listing (if it was fetched) so that posts dropped by the previous
+    /// filters are listed again. Comments already loaded for the posts are
+    /// kept.
+    fn refilter_page(
+        &mut self,
+        conn: &Connection,
+        mode: &Mode,
+        mut posts: Vec<Post>,
+    ) -> Result<Vec<Post>> {
+        let base_url = self.instance().base_url.clone();
+        let mut refiltered = match get_listing::<Vec<Post>>(conn, &base_url, &mode.path())? {
+            Some(cached) if !mode.is_local() => cached.data,
+            _ => posts.clone(),
+        };
+
+        for post in refiltered.iter_mut() {
+            if let Some(prev) = posts.iter_mut().find(|p| p.short_id == post.short_id) {
+                post.comments = mem::take(&mut prev.comments);
+            }
+        }
+        let show_hidden = self.show_hidden.load(Ordering::Relaxed);
+        let hidden = update_posts(conn, &base_url, &mut refiltered, show_hidden)?;
+        self.filter_posts(mode, &mut refiltered, hidden);
+
+        Ok(refiltered)
+    }
+
+    /// Filter the posts loaded for the instance being browsed again, e.g.
+    /// after changing which tags are filtered, without fetching them again.
+    /// Other instances are loaded again when next browsed.
+    pub(super) fn refilter_posts(&mut self) -> Result<()> {
+        let conn = get_db_connection(&self.db)?;
+
+        let mut mode = self.mode.clone();
+        for page in 0..self.cache_posts.len() {
+            let posts = mem::take(&mut self.cache_posts[page]);
+            if posts.is_empty() {
+                continue;
+            }
+            mode.set_page(page as u8 + 1);
+            self.cache_posts[page] = self.refilter_page(&conn, &mode, posts)?;
+        }
+
+        for key in self.cache_modes.keys().cloned().collect::<Vec<_>>() {
+            let (mut mode, pages) = self.cache_modes.remove(&key).expect("key should be cached");
+            let mut refiltered = Vec::with_capacity(pages.len());
+            for (page, posts) in pages.into_iter().enumerate() {
+                if posts.is_empty() || mode.lists_comments() {
+                    refiltered.push(posts);
+                    continue;
+                }
+                mode.set_page(page as u8 + 1);
+                refiltered.push(self.refilter_page(&conn, &mode, posts)?);
+            }
+            self.cache_modes.insert(key, (mode, refiltered));
+        }
+
+        if !self.mode.lists_comments() && !self.posts.is_empty() {
+            let posts = self.refilter_page(&conn, &self.mode.clone(), self.posts.clone())?;
+            self.replace_posts(posts);
+        }
+
+        for state in self.instance_states.iter_mut().flatten() {
+            state.clear_posts();
+        }
+        Ok(())
+    }
+
+    /// Show or close the popup listing which filter rules match the selected
+    /// post.
+    pub(super) fn toggle_filters_popup(&mut self) {
+        self.show_filters_popup = !self.show_filters_popup && self.current_post().is_some();
+    }
+
+    /// Select the next tag in the details popup's Tags row, wrapping around.
+    pub(super) fn next_tag(&mut self) {
+        let Some(tags) = self.current_post().map(|p| p.tags.len()) else {
+            return;
+        };
+        if tags == 0 {
+            return;
+        }
+
+        self.selected_tag = Some(self.selected_tag.map_or(0, |i| (i + 1) % tags));
+    }
+
+    /// Select the previous tag in the details popup's Tags row, wrapping
+    /// around.
+    pub(super) fn previous_tag(&mut self) {
+        let Some(tags) = self.current_post().map(|p| p.tags.len()) else {
+            return;
+        };
+        if tags == 0 {
+            return;
+        }
+
+        self.selected_tag = Some(
+            self.selected_tag
+                .map_or(tags - 1, |i| (i + tags - 1) % tags),
+        );
+    }
+
+    fn selected_tag(&self) -> Option<String> {
+        self.current_post()?.tags.get(self.selected_tag?).cloned()
+    }
+
+    /// Mute the tag selected in the details popup, or unmute it if it is
+    /// already muted.
+    pub(super) fn toggle_tag_muted(&mut self) -> Result<()> {
+        let Some(tag) = self.selected_tag() else {
+            return Ok(());
+        };
+
+        self.config.tag_filter.toggle_muted(&tag);
+        self.refilter_posts()
+    }
+
+    /// Only show posts with the tag selected in the details popup, or stop
+    /// doing so if already the case.
+    pub(super) fn toggle_tag_only(&mut self) -> Result<()> {
+        let Some(tag) = self.selected_tag() else {
+            return Ok(());
+        };
+
+        self.config.tag_filter.toggle_only(&tag);
+        self.refilter_posts()
+    }
+
+    /// Switch between hiding and dimming posts filtered out by their tags.
+    pub(super) fn toggle_tag_filter_action(&mut self) -> Result<()> {
+        self.config.tag_filter.action = self.config.tag_filter.action.toggle();
+        self.refilter_posts()
+    }
+}
@@ -0,0 +1,104 @@
+//! Hidden posts - hide posts from listings, undo hiding them and toggle
+//! showing them again.
+use std::sync::{Arc, atomic::Ordering, mpsc::Sender};
+
+use color_eyre::{Result, eyre::Context};
+
+use super::{App, HiddenPost, action::{DatabaseAction, PostsRequest}};
+
+impl App {
+    /// Hide the post at the given index from listings. Unless hidden posts are
+    /// being shown, it is removed from the page straight away.
+    pub(super) fn hide_post(&mut self, index: usize, tx: &Sender<DatabaseAction>) -> Result<()> {
+        let Some(post) = self.posts.get_mut(index) else {
+            return Ok(());
+        };
+
+        // Already hidden
+        if post.is_hidden {
+            return Ok(());
+        }
+
+        post.is_hidden = true;
+        tx.send(DatabaseAction::HidePost {
+            instance: self.instances[self.instance].base_url.clone(),
+            id: post.short_id.clone(),
+        })
+        .context("hide post channel is closed")?;
+
+        let post = if self.show_hidden.load(Ordering::Relaxed) {
+            self.posts[index].clone()
+        } else {
+            let post = self.posts.remove(index);
+            self.show_details_popup = false;
+            if self.posts.is_empty() {
+                self.posts_list_state.select(None);
+            } else {
+                self.select_row(index.min(self.posts.len() - 1));
+            }
+            post
+        };
+
+        let path = self.mode.path();
+        self.listing_counts.entry(path.clone()).or_default().hidden += 1;
+        self.last_hidden = Some(HiddenPost {
+            instance: Arc::clone(self.instance()),
+            path,
+            index,
+            post,
+        });
+
+        Ok(())
+    }
+
+    /// Stop hiding the post which was hidden last. If it was hidden from the
+    /// page being browsed it is listed again straight away, otherwise once its
+    /// listing is loaded again.
+    pub(super) fn undo_hide(&mut self, tx: &Sender<DatabaseAction>) -> Result<()> {
+        let Some(HiddenPost {
+            instance,
+            path,
+            index,
+            mut post,
+        }) = self.last_hidden.take()
+        else {
+            return Ok(());
+        };
+
+        tx.send(DatabaseAction::UnhidePost {
+            instance: instance.base_url.clone(),
+            id: post.short_id.clone(),
+        })
+        .context("unhide post channel is closed")?;
+
+        if let Some(counts) = self
+            .listing_counts_of(&instance)
+            .and_then(|counts| counts.get_mut(&path))
+        {
+            counts.hidden = counts.hidden.saturating_sub(1);
+        }
+
+        if !self.is_current_instance(&instance) || path != self.mode.path() {
+            return Ok(());
+        }
+
+        match self.posts.iter().position(|p| p.short_id == post.short_id) {
+            // Hidden posts are being shown
+            Some(index) => self.posts[index].is_hidden = false,
+            None => {
+                post.is_hidden = false;
+                let index = index.min(self.posts.len());
+                self.posts.insert(index, post);
+                self.select_row(index);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Toggle showing hidden posts in listings.
+    pub(super) fn toggle_show_hidden(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        self.show_hidden.fetch_xor(true, Ordering::Relaxed);
+        self.reload_posts(tx)
+    }
+}
@@ -0,0 +1,131 @@
+//! Instance-related functionality - switch between the configured instances,
+//! keeping the state of each separate.
+use std::{collections::HashMap, mem, sync::{Arc, atomic::Ordering, mpsc::Sender}};
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use ratatui::widgets::{ListState, ScrollbarState};
+
+use super::{App, Instance, ListingCounts, TABLE_ROW_HEIGHT, action::PostsRequest};
+use crate::{data::{Post, PostComment}, modes::Mode, panic};
+
+/// State of an instance which is not currently being browsed.
+#[derive(Debug)]
+pub(super) struct InstanceState {
+    mode: Mode,
+    posts: Vec<Post>,
+    comments: Vec<PostComment>,
+    cache_posts: Vec<Vec<Post>>,
+    cache_modes: HashMap<String, (Mode, Vec<Vec<Post>>)>,
+    cache_comments: HashMap<String, Vec<PostComment>>,
+    refreshed_at: HashMap<String, DateTime<Utc>>,
+    listing_counts: HashMap<String, ListingCounts>,
+    posts_list_state: ListState,
+    posts_scroll_state: ScrollbarState,
+}
+
+impl InstanceState {
+    /// State of an instance which has not been browsed yet.
+    fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            posts: Default::default(),
+            comments: Default::default(),
+            cache_posts: Default::default(),
+            cache_modes: Default::default(),
+            cache_comments: Default::default(),
+            refreshed_at: Default::default(),
+            listing_counts: Default::default(),
+            posts_list_state: ListState::default().with_selected(Some(0)),
+            posts_scroll_state: ScrollbarState::new(25 * TABLE_ROW_HEIGHT),
+        }
+    }
+
+    /// Drop the posts loaded for the instance, so they are loaded again when
+    /// it is browsed.
+    pub(super) fn clear_posts(&mut self) {
+        self.posts.clear();
+        self.cache_posts.clear();
+        self.cache_modes.clear();
+    }
+}
+
+impl App {
+    /// The instance currently being browsed.
+    pub(super) fn instance(&self) -> &Arc<Instance> {
+        &self.instances[self.instance]
+    }
+
+    /// Whether the given instance is the one currently being browsed.
+    pub(super) fn is_current_instance(&self, instance: &Arc<Instance>) -> bool {
+        Arc::ptr_eq(instance, self.instance())
+    }
+
+    /// Counts of the listings browsed on the given instance, whether it is the
+    /// one currently being browsed or not.
+    pub(super) fn listing_counts_of(
+        &mut self,
+        instance: &Arc<Instance>,
+    ) -> Option<&mut HashMap<String, ListingCounts>> {
+        if self.is_current_instance(instance) {
+            return Some(&mut self.listing_counts);
+        }
+
+        let index = self
+            .instances
+            .iter()
+            .position(|i| Arc::ptr_eq(i, instance))?;
+        self.instance_states[index]
+            .as_mut()
+            .map(|state| &mut state.listing_counts)
+    }
+
+    fn swap_instance_state(&mut self, state: &mut InstanceState) {
+        mem::swap(&mut self.mode, &mut state.mode);
+        mem::swap(&mut self.posts, &mut state.posts);
+        mem::swap(&mut self.comments, &mut state.comments);
+        mem::swap(&mut self.cache_posts, &mut state.cache_posts);
+        mem::swap(&mut self.cache_modes, &mut state.cache_modes);
+        mem::swap(&mut self.cache_comments, &mut state.cache_comments);
+        mem::swap(&mut self.refreshed_at, &mut state.refreshed_at);
+        mem::swap(&mut self.listing_counts, &mut state.listing_counts);
+        mem::swap(&mut self.posts_list_state, &mut state.posts_list_state);
+        mem::swap(&mut self.posts_scroll_state, &mut state.posts_scroll_state);
+    }
+
+    /// Switch to browsing the next instance, restoring its state from when it
+    /// was last browsed.
+    pub(super) fn next_instance(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        if self.instances.len() < 2 {
+            return Ok(());
+        }
+
+        self.show_details_popup = false;
+        // Errors and retries are for the instance being switched away from
+        self.fetch_error = None;
+        self.fallback_mode = None;
+        self.posts_attempts_left = None;
+        self.details_attempts_left = None;
+        self.prefetching_pages.clear();
+
+        let mut state = InstanceState::new(self.config.default_mode.clone());
+        self.swap_instance_state(&mut state);
+        self.instance_states[self.instance] = Some(state);
+
+        self.instance = (self.instance + 1) % self.instances.len();
+        let mut state = self.instance_states[self.instance]
+            .take()
+            .unwrap_or_else(|| InstanceState::new(self.config.default_mode.clone()));
+        self.swap_instance_state(&mut state);
+
+        panic::set_instance(&self.instance().base_url);
+
+        if self.list_len() == 0 {
+            self.load_posts(tx)?;
+        } else {
+            self.is_loading.store(false, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+//! Command palette - run any command by (fuzzy searching for) its name, along
+//! with commands which take arguments and have no key.
+use color_eyre::{Result, eyre::eyre};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::{App, command::{Channels, Command}};
+use crate::{config::KeyAction, data::Post, modes::Mode, utils::fuzzy_score};
+
+/// Commands which take an argument, along with a description of it.
+const COMMANDS_WITH_ARGS: [(&str, &str, &str); 4] = [
+    ("mode", "<mode>", "Switch mode, e.g. newest or tag:rust"),
+    ("page", "<page>", "Go to page"),
+    ("open", "<id>", "Open post by its short ID"),
+    ("find", "<query>", "Search everything seen for the query"),
+];
+
+/// Text typed into the command palette, and the entry selected.
+#[derive(Debug, Default)]
+pub(super) struct Palette {
+    pub input: String,
+    pub selected: usize,
+    /// Error from the last command which could not be run
+    pub error: Option<String>,
+}
+
+impl Palette {
+    fn select_next(&mut self, entries: usize) {
+        self.selected = (self.selected + 1).min(entries.saturating_sub(1));
+    }
+
+    fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Command listed in the command palette.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteEntry {
+    pub name: String,
+    /// Argument taken by the command, if any
+    pub arg: Option<&'static str>,
+    pub description: &'static str,
+    /// Keys bound to the command
+    pub keys: String,
+    action: Option<KeyAction>,
+}
+
+impl App {
+    pub(super) fn open_palette(&mut self) {
+        self.palette = Some(Palette::default());
+    }
+
+    /// Entries matching the command typed into the palette, best match first.
+    pub(super) fn palette_entries(&self) -> Vec<PaletteEntry> {
+        let Some(palette) = &self.palette else {
+            return Vec::new();
+        };
+        let name = palette.input.split_whitespace().next().unwrap_or_default();
+
+        let actions = self
+            .config
+            .keybinds
+            .bindings()
+            .iter()
+            .filter(|(action, _)| *action != KeyAction::CommandPalette)
+            .map(|(action, chords)| {
+                let keys: Vec<String> = chords.iter().map(|c| c.label()).collect();
+                PaletteEntry {
+                    name: action.to_string(),
+                    arg: None,
+                    description: action.description(),
+                    keys: keys.join(" / "),
+                    action: Some(*action),
+                }
+            });
+        let with_args = COMMANDS_WITH_ARGS
+            .iter()
+            .map(|(name, arg, description)| PaletteEntry {
+                name: name.to_string(),
+                arg: Some(arg),
+                description,
+                keys: String::new(),
+                action: None,
+            });
+
+        let mut entries: Vec<(usize, PaletteEntry)> = actions
+            .chain(with_args)
+            .filter_map(|entry| {
+                let score =
+                    fuzzy_score(name, &entry.name).max(fuzzy_score(name, entry.description))?;
+                Some((score, entry))
+            })
+            .collect();
+        // Stable, so keeps the order of the keybinds popup for equal scores
+        entries.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    /// Handle a key pressed while the command palette is open.
+    pub(super) fn handle_palette_key(&mut self, ev: KeyEvent, tx: &Channels) -> Result<()> {
+        let entries = self.palette_entries();
+        let domain = self.current_post().and_then(Post::domain);
+        let Some(palette) = &mut self.palette else {
+            return Ok(());
+        };
+        let is_ctrl = ev.modifiers.contains(KeyModifiers::CONTROL);
+
+        match ev.code {
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Char('c') if is_ctrl => self.palette = None,
+            KeyCode::Enter => {
+                let command = parse_command(
+                    &palette.input,
+                    entries.get(palette.selected),
+                    domain.as_deref(),
+                );
+                match command {
+                    Ok(Some(command)) => {
+                        self.palette = None;
+                        let toggles_popup = command.toggles_popup();
+                        self.dispatch(command, tx)?;
+                        if !toggles_popup {
+                            self.show_keybinds_popup = false;
+                            self.stats_popup = None;
+                            self.show_filters_popup = false;
+                        }
+                    }
+                    // Needs an argument
+                    Ok(None) => complete(palette, entries.get(palette.selected)),
+                    Err(e) => palette.error = Some(format!("{e}")),
+                }
+            }
+            KeyCode::Tab => complete(palette, entries.get(palette.selected)),
+            KeyCode::Down => palette.select_next(entries.len()),
+            KeyCode::Char('n') if is_ctrl => palette.select_next(entries.len()),
+            KeyCode::Up => palette.select_previous(),
+            KeyCode::Char('p') if is_ctrl => palette.select_previous(),
+            KeyCode::Backspace if palette.input.is_empty() => self.palette = None,
+            KeyCode::Backspace => {
+                palette.input.pop();
+                palette.selected = 0;
+                palette.error = None;
+            }
+            KeyCode::Char(c) if !is_ctrl => {
+                palette.input.push(c);
+                palette.selected = 0;
+                palette.error = None;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Fill in the name of the entry, ready for its argument to be typed.
+fn complete(palette: &mut Palette, entry: Option<&PaletteEntry>) {
+    let Some(entry) = entry else {
+        return;
+    };
+
+    palette.input = entry.name.clone();
+    if entry.arg.is_some() {
+        palette.input.push(' ');
+    }
+    palette.selected = 0;
+    palette.error = None;
+}
+
+/// Parse the command typed into the palette, falling back to the selected entry
+/// if only (part of) a name was typed. Returns `None` if the command still
+/// needs an argument. `domain` is the selected story's host, used by a
+/// `domain` mode given no domain.
+fn parse_command(
+    input: &str,
+    selected: Option<&PaletteEntry>,
+    domain: Option<&str>,
+) -> Result<Option<Command>> {
+    let input = input.trim();
+    let (name, arg) = match input.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, Some(arg.trim())),
+        None => (input, None),
+    };
+
+    let Some(arg) = arg else {
+        let Some(entry) = selected else {
+            return Err(eyre!("No matching command"));
+        };
+        return Ok(entry.action.map(Command::from));
+    };
+
+    let command = match name {
+        "mode" => Command::SetMode(Mode::parse(arg, domain)?),
+        "page" => match arg.parse() {
+            Ok(page) if page > 0 => Command::GoToPage(page),
+            _ => return Err(eyre!("Not a valid page: '{arg}'")),
+        },
+        // Also accept links to the post
+        "open" => {
+            let id = arg.rsplit("/s/").next().unwrap_or(arg);
+            Command::OpenById(id.split('/').next().unwrap_or(id).to_owned())
+        }
+        "find" => Command::SearchSeen(arg.to_owned()),
+        _ => return Err(eyre!("Unknown command: '{name}'")),
+    };
+
+    Ok(Some(command))
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::app::command::test::app;
+
+    fn names(app: &App) -> Vec<String> {
+        app.palette_entries().into_iter().map(|e| e.name).collect()
+    }
+
+    fn type_keys(app: &mut App, tx: &Channels, keys: &str) {
+        for c in keys.chars() {
+            app.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE), tx)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn parse_commands() {
+        let parse = |input| parse_command(input, None, None).unwrap().unwrap();
+        assert_eq!(parse("mode newest"), Command::SetMode(Mode::Newest(1)));
+        assert_eq!(parse(" page 7 "), Command::GoToPage(7));
+        assert_eq!(parse("open abc123"), Command::OpenById("abc123".into()));
+        assert_eq!(
+            parse("find io_uring"),
+            Command::SearchSeen("io_uring".into())
+        );
+        assert_eq!(
+            parse("open https://lobste.rs/s/abc123/some_title"),
+            Command::OpenById("abc123".into())
+        );
+
+        assert!(parse_command("page 0", None, None).is_err());
+        assert!(parse_command("page x", None, None).is_err());
+        assert!(parse_command("unknown 1", None, None).is_err());
+        assert!(parse_command("next", None, None).is_err());
+        assert!(parse_command("mode domain", None, None).is_err());
+    }
+
+    #[test]
+    fn mode_domain_of_selected_post() {
+        let (mut app, tx, _rx) = app();
+        type_keys(&mut app, &tx, ":mode domain");
+        app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &tx)
+            .unwrap();
+        assert!(app.palette.is_none());
+        assert_eq!(app.mode, Mode::Domain("example.com".into(), 1));
+    }
+
+    #[test]
+    fn filter_and_run() {
+        let (mut app, tx, _rx) = app();
+        type_keys(&mut app, &tx, ":");
+        assert!(app.palette.is_some());
+        assert_eq!(
+            names(&app).len(),
+            KeyAction::ALL.len() - 1 + COMMANDS_WITH_ARGS.len()
+        );
+
+        type_keys(&mut app, &tx, "nxtpg");
+        assert_eq!(names(&app)[0], "next_page");
+
+        // Selected entry needs an argument, so is completed
+        app.palette = None;
+        type_keys(&mut app, &tx, ":pag");
+        assert_eq!(names(&app)[0], "page");
+        app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &tx)
+            .unwrap();
+        assert_eq!(app.palette.as_ref().unwrap().input, "page ");
+
+        type_keys(&mut app, &tx, "2");
+        app.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE), &tx)
+            .unwrap();
+        assert!(app.palette.is_none());
+        assert_eq!(app.mode, Mode::Hottest(2));
+    }
+}
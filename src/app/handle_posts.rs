@@ -1,67 +1,198 @@
-use std::sync::{atomic::Ordering, mpsc::Sender};
+use std::{io::{self, Read}, mem, sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::Sender}};
 
 use color_eyre::{Result, Section, eyre::Context};
-use reqwest::blocking::Client;
+use reqwest::{StatusCode, blocking::{Client, Request, Response}};
+
+use super::{App, Instance, action::{DatabaseAction, DetailsRequest, Fetch, PostsRequest, Purpose}, retry::execute_with_retry};
+use crate::{config::RetryConfig, data::{Bandwidth, Post, PostComment, PostDetails, Validators}, modes::Mode};
+
+/// Result of a request which is conditional on the data having changed.
+#[derive(Debug)]
+pub(super) enum Fetched<T> {
+    /// Changed, along with the validators for the new data and the bytes
+    /// downloaded
+    Modified(T, Validators, Bandwidth),
+    /// Not changed since the validators sent with the request were received
+    NotModified,
+}
+
+/// Build a GET request for the URL, which is conditional on the data having
+/// changed if there are validators for it.
+fn conditional_get(client: &Client, url: &str, validators: Option<&Validators>) -> Result<Request> {
+    let mut req = client.get(url);
+    if let Some(validators) = validators {
+        req = validators.apply(req);
+    }
+
+    req.build().context("failed to build request")
+}
+
+/// What is being downloaded, for counting the bytes downloaded.
+#[derive(Debug, Clone, Copy)]
+enum Content {
+    Listing,
+    Comments,
+}
+
+/// Read the whole body of the response, counting the bytes read for the
+/// instance.
+fn read_body(
+    mut resp: Response,
+    instance: &Instance,
+    content: Content,
+    purpose: Purpose,
+) -> io::Result<(Vec<u8>, Bandwidth)> {
+    let mut body = Vec::new();
+    resp.read_to_end(&mut body)?;
+
+    let len = body.len() as u64;
+    let mut bandwidth = match content {
+        Content::Listing => Bandwidth {
+            listings: len,
+            ..Default::default()
+        },
+        Content::Comments => Bandwidth {
+            comments: len,
+            ..Default::default()
+        },
+    };
+    if purpose == Purpose::Prefetch {
+        bandwidth.prefetched = len;
+    }
+    instance.downloaded.add(&bandwidth);
+
+    Ok((body, bandwidth))
+}
 
-use super::{App, DownloadedBytes, action::DatabaseAction};
-use crate::{data::{Post, PostDetails}, modes::Mode};
+#[allow(clippy::too_many_arguments)]
 pub(super) fn fetch_posts(
     client: &Client,
+    instance: &Instance,
     mode: &Mode,
-    downloaded: &mut DownloadedBytes,
-) -> Result<Vec<Post>> {
-    let url = mode.get_url();
-    let req = client.get(url).build().context("failed to build request")?;
-
-    let resp = client
-        .execute(req)
+    validators: Option<&Validators>,
+    purpose: Purpose,
+    retry: &RetryConfig,
+    is_running: &AtomicBool,
+    on_retry: impl Fn(u32),
+) -> Result<Fetched<Vec<Post>>> {
+    let url = mode.get_url(&instance.base_url);
+    let req = conditional_get(client, &url, validators)?;
+
+    let resp = execute_with_retry(client, req, retry, is_running, on_retry)
         .context("failed requesting posts")
-        .suggestion("check internet access")?;
+        .suggestion("check internet access")?
+        .error_for_status()
+        .context("failed requesting posts")?;
 
-    if let Some(len) = resp.content_length() {
-        let _ = downloaded.fetch_add(len as u32, Ordering::Relaxed);
-    };
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
 
-    resp.json::<Vec<Post>>()
+    let validators = Validators::from_headers(resp.headers());
+    let (body, bandwidth) =
+        read_body(resp, instance, Content::Listing, purpose).context("failed reading posts")?;
+    serde_json::from_slice::<Vec<Post>>(&body)
+        .map(|posts| Fetched::Modified(posts, validators, bandwidth))
         .context("failed converting posts from JSON - maybe the format has changed?")
 }
 
+#[allow(clippy::too_many_arguments)]
+pub(super) fn fetch_comments(
+    client: &Client,
+    instance: &Instance,
+    mode: &Mode,
+    validators: Option<&Validators>,
+    purpose: Purpose,
+    retry: &RetryConfig,
+    is_running: &AtomicBool,
+    on_retry: impl Fn(u32),
+) -> Result<Fetched<Vec<PostComment>>> {
+    let url = mode.get_url(&instance.base_url);
+    let req = conditional_get(client, &url, validators)?;
+
+    let resp = execute_with_retry(client, req, retry, is_running, on_retry)
+        .context("failed requesting comments")
+        .suggestion("check internet access")?
+        .error_for_status()
+        .context("failed requesting comments")?;
+
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
+
+    let validators = Validators::from_headers(resp.headers());
+    let (body, bandwidth) =
+        read_body(resp, instance, Content::Listing, purpose).context("failed reading comments")?;
+    serde_json::from_slice::<Vec<PostComment>>(&body)
+        .map(|comments| Fetched::Modified(comments, validators, bandwidth))
+        .context("failed converting comments from JSON - maybe the format has changed?")
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(super) fn fetch_post_details(
     client: &Client,
-    url: impl AsRef<str>,
-    downloaded: &mut DownloadedBytes,
-) -> Result<PostDetails> {
-    let url = format!("{}.json", url.as_ref());
-
-    let req = client
-        .get(&url)
-        .build()
-        .context("failed to build request")?;
-
-    let resp = client
-        .execute(req)
+    instance: &Instance,
+    short_id: impl AsRef<str>,
+    validators: Option<&Validators>,
+    purpose: Purpose,
+    retry: &RetryConfig,
+    is_running: &AtomicBool,
+    on_retry: impl Fn(u32),
+) -> Result<Fetched<PostDetails>> {
+    let url = format!("{}/s/{}.json", instance.base_url, short_id.as_ref());
+    let req = conditional_get(client, &url, validators)?;
+
+    let resp = execute_with_retry(client, req, retry, is_running, on_retry)
+        .context("failed requesting post details")?
+        .error_for_status()
         .context("failed requesting post details")?;
 
-    if let Some(len) = resp.content_length() {
-        let _ = downloaded.fetch_add(len as u32, Ordering::Relaxed);
-    };
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
 
-    resp.json::<PostDetails>().context(format!(
-        "failed converting post details from JSON. Url: {url}"
-    ))
+    let validators = Validators::from_headers(resp.headers());
+    let (body, bandwidth) = read_body(resp, instance, Content::Comments, purpose)
+        .context("failed reading post details")?;
+    serde_json::from_slice::<PostDetails>(&body)
+        .map(|details| Fetched::Modified(details, validators, bandwidth))
+        .context(format!(
+            "failed converting post details from JSON. Url: {url}"
+        ))
 }
 
 impl App {
-    pub(super) fn load_posts(&self, tx: &Sender<Mode>) -> Result<()> {
+    pub(super) fn load_posts(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
         self.is_loading.store(true, Ordering::Relaxed);
-        tx.send(self.mode.clone())
-            .context("load posts channel is closed")
+        self.refresh_posts(tx)
+    }
+
+    /// Fetch the posts for the current mode, without hiding those already being
+    /// shown while loading.
+    pub(super) fn refresh_posts(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        // Queued prefetches are dropped in favour of this request
+        self.prefetching_pages.clear();
+        tx.send((
+            Arc::clone(self.instance()),
+            self.mode.clone(),
+            Purpose::Load,
+        ))
+        .context("load posts channel is closed")
+    }
+
+    /// Whether the given fetch is for what is currently being browsed, rather
+    /// than for a mode or page which has since been switched away from.
+    pub(super) fn is_current_fetch(&self, fetch: &Fetch) -> bool {
+        match fetch {
+            Fetch::Posts(mode) => *mode == self.mode,
+            Fetch::PostDetails => true,
+        }
     }
 
     pub(super) fn load_post_comments(
         &mut self,
         index: usize,
-        tx_details: &Sender<String>,
+        tx_details: &Sender<DetailsRequest>,
         tx_db: &Sender<DatabaseAction>,
     ) -> Result<()> {
         if self.config.previewing_comments_marks_posts_read {
@@ -73,10 +204,16 @@ impl App {
             return Ok(());
         };
 
-        self.is_loading_comments.store(true, Ordering::Relaxed);
-        let url = post.short_id_url.clone();
+        // Already loaded (e.g. prefetched) - show them while refreshing
+        if post.comments.is_empty() {
+            self.is_loading_comments.store(true, Ordering::Relaxed);
+        }
         tx_details
-            .send(url)
+            .send((
+                Arc::clone(self.instance()),
+                post.short_id.clone(),
+                Purpose::Load,
+            ))
             .context("load post details channel is closed")
     }
 
@@ -112,8 +249,11 @@ impl App {
         // program is launched
         post.is_read = true;
 
-        tx.send(DatabaseAction::MarkPostRead(post.short_id.clone()))
-            .context("mark post read channel is closed")
+        tx.send(DatabaseAction::MarkPostRead {
+            instance: self.instances[self.instance].base_url.clone(),
+            post: Box::new(post.clone()),
+        })
+        .context("mark post read channel is closed")
     }
 
     pub(super) fn mark_post_unread(
@@ -121,18 +261,61 @@ impl App {
         index: usize,
         tx: &Sender<DatabaseAction>,
     ) -> Result<()> {
-        assert!(
-            index < self.posts.len(),
-            "trying to open post out of bounds"
-        );
+        let Some(post) = self.posts.get_mut(index) else {
+            return Ok(());
+        };
 
         // Mark unread straight away - DB status will only matter the next time the
         // program is launched
-        let post = &mut self.posts[index];
         post.is_read = false;
 
-        tx.send(DatabaseAction::MarkPostUnread(post.short_id.clone()))
-            .context("mark post read channel is closed")
+        tx.send(DatabaseAction::MarkPostUnread {
+            instance: self.instances[self.instance].base_url.clone(),
+            id: post.short_id.clone(),
+        })
+        .context("mark post read channel is closed")
+    }
+
+    /// Save the post at the given index to read later.
+    pub(super) fn save_post(&mut self, index: usize, tx: &Sender<DatabaseAction>) -> Result<()> {
+        let Some(post) = self.posts.get_mut(index) else {
+            return Ok(());
+        };
+
+        post.is_saved = true;
+
+        tx.send(DatabaseAction::SavePost {
+            instance: self.instances[self.instance].base_url.clone(),
+            post: Box::new(post.clone()),
+        })
+        .context("save post channel is closed")
+    }
+
+    /// Remove the post at the given index from the posts saved to read later.
+    /// When browsing saved posts, it stays listed until they are loaded again.
+    pub(super) fn unsave_post(&mut self, index: usize, tx: &Sender<DatabaseAction>) -> Result<()> {
+        let Some(post) = self.posts.get_mut(index) else {
+            return Ok(());
+        };
+
+        post.is_saved = false;
+
+        tx.send(DatabaseAction::UnsavePost {
+            instance: self.instances[self.instance].base_url.clone(),
+            id: post.short_id.clone(),
+        })
+        .context("unsave post channel is closed")
+    }
+
+    /// Open the post with the given short ID, if listed on the page, otherwise
+    /// its comments on the current instance.
+    pub(super) fn open_post_by_id(&mut self, id: &str, tx: &Sender<DatabaseAction>) -> Result<()> {
+        if let Some(index) = self.posts.iter().position(|p| p.short_id == id) {
+            return self.open_post(index, tx);
+        }
+
+        let url = format!("{}/s/{id}", self.instance().base_url);
+        open::that_detached(url).context("failed to launch link opener")
     }
 
     pub(super) fn open_post_comments(
@@ -159,23 +342,164 @@ impl App {
         else {
             return Ok(());
         };
-        assert!(
-            index < post.comments.len(),
-            "trying to open comment out of bounds"
-        );
+        let Some(comment) = post.comments.get(index) else {
+            return Ok(());
+        };
 
-        open::that_detached(&post.comments[index].url).context("failed to launch link opener")?;
+        open::that_detached(&comment.url).context("failed to launch link opener")
+    }
 
-        Ok(())
+    /// Open the comment at the given index, for modes which list comments.
+    pub(super) fn open_listed_comment(&self, index: usize) -> Result<()> {
+        let Some(comment) = self.comments.get(index) else {
+            return Ok(());
+        };
+
+        open::that_detached(&comment.url).context("failed to launch link opener")
     }
 
-    pub(super) fn current_post(&self) -> Option<&Post> {
+    /// Open the post which the comment at the given index was made on, for
+    /// modes which list comments.
+    pub(super) fn open_listed_comment_story(&self, index: usize) -> Result<()> {
+        let base_url = &self.instance().base_url;
+        let Some(url) = self.comments.get(index).and_then(|c| c.story_url(base_url)) else {
+            return Ok(());
+        };
+
+        open::that_detached(url).context("failed to launch link opener")
+    }
+
+    /// Username of the author of the selected comment if comments are being
+    /// listed, or one is selected in the details popup, otherwise of the
+    /// selected post's submitter.
+    pub(super) fn selected_user(&self) -> Option<&str> {
         let index = self.posts_list_state.selected()?;
+
+        if self.mode.lists_comments() {
+            return self.comments.get(index).map(|c| c.commenting_user.as_str());
+        }
+
+        let post = self.posts.get(index)?;
+        if self.show_details_popup
+            && let Some(comment) = self
+                .comments_list_state
+                .selected()
+                .and_then(|i| post.comments.get(i))
+        {
+            return Some(comment.commenting_user.as_str());
+        }
+
+        Some(post.submitter_user.as_str())
+    }
+
+    /// Replace the listed posts with newly loaded ones, keeping the selected
+    /// post selected and any comments already loaded for posts still listed.
+    pub(super) fn replace_posts(&mut self, mut posts: Vec<Post>) {
+        // Search the new posts instead
+        let search = self.suspend_search();
+        let selected = self
+            .posts_list_state
+            .selected()
+            .and_then(|i| self.posts.get(i))
+            .map(|p| p.short_id.clone());
+
+        for post in posts.iter_mut() {
+            if let Some(prev) = self.posts.iter_mut().find(|p| p.short_id == post.short_id) {
+                post.comments = mem::take(&mut prev.comments);
+            }
+        }
+        self.posts = posts;
+
+        match selected.and_then(|id| self.posts.iter().position(|p| p.short_id == id)) {
+            Some(index) => self.select_row(index),
+            None => {
+                // Details popup would show a different post
+                self.show_details_popup = false;
+                self.first_row();
+            }
+        }
+
+        self.resume_search(search);
+    }
+
+    /// Replace the listed comments with newly loaded ones, for modes which list
+    /// comments, keeping the selected comment selected.
+    pub(super) fn replace_comments(&mut self, comments: Vec<PostComment>) {
+        let search = self.suspend_search();
+        let selected = self
+            .posts_list_state
+            .selected()
+            .and_then(|i| self.comments.get(i))
+            .map(|c| c.url.clone());
+
+        self.comments = comments;
+
+        match selected.and_then(|url| self.comments.iter().position(|c| c.url == url)) {
+            Some(index) => self.select_row(index),
+            None => self.first_row(),
+        }
+
+        self.resume_search(search);
+    }
+
+    /// The selected post, if any are listed.
+    pub(super) fn current_post(&self) -> Option<&Post> {
+        self.posts_list_state
+            .selected()
+            .and_then(|i| self.posts.get(i))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::app::stub_server::stub_server;
+
+    #[test]
+    fn conditional_requests() {
+        let (url, requests) = stub_server(&[
+            "200 OK|ETag: \"abc\"\r\n",
+            "304 Not Modified|ETag: \"abc\"\r\n",
+        ]);
+        let instance = Instance::new(String::from("stub"), url);
+        let client = Client::new();
+        let retry = RetryConfig::default();
+        let fetch = |validators| {
+            let mode = Mode::Comments(1);
+            fetch_comments(
+                &client,
+                &instance,
+                &mode,
+                validators,
+                Purpose::Load,
+                &retry,
+                &AtomicBool::new(true),
+                |_| {},
+            )
+        };
+
+        let Fetched::Modified(comments, validators, bandwidth) = fetch(None).unwrap() else {
+            panic!("comments should have been fetched");
+        };
+        assert!(comments.is_empty());
+        assert_eq!(validators.etag.as_deref(), Some("\"abc\""));
+
+        assert!(matches!(
+            fetch(Some(&validators)).unwrap(),
+            Fetched::NotModified
+        ));
+        // Nothing downloaded for the second request
+        assert_eq!(instance.downloaded.load(), bandwidth);
+        assert_eq!(bandwidth.listings, 2);
+
+        let requests = requests.lock().unwrap();
+        assert!(!requests[0].to_lowercase().contains("if-none-match"));
         assert!(
-            index < self.posts.len(),
-            "trying to open post out of bounds"
+            requests[1]
+                .to_lowercase()
+                .contains("if-none-match: \"abc\"")
         );
-
-        Some(&self.posts[index])
     }
 }
@@ -0,0 +1,87 @@
+//! Prefetch-related functionality - fetch what is likely to be browsed next in
+//! the background, within the configured limits.
+use std::sync::{Arc, atomic::Ordering, mpsc::Sender};
+
+use color_eyre::{Result, eyre::Context};
+
+use super::{App, Instance, action::{DetailsRequest, PostsRequest, Purpose}};
+use crate::modes::Mode;
+
+/// Something to fetch in the background.
+#[derive(Debug)]
+pub(super) enum Prefetch {
+    /// A page of the listing for a mode
+    Page(Arc<Instance>, Mode),
+    /// The details of the post with the given short ID
+    PostDetails(Arc<Instance>, String),
+}
+
+impl App {
+    /// Plan prefetches for what is likely to be browsed next from the current
+    /// page, replacing any planned from the previous one.
+    pub(super) fn plan_prefetch(&mut self) {
+        self.prefetch_queue.clear();
+        if self.config.offline || self.mode.lists_comments() || self.mode.is_local() {
+            return;
+        }
+
+        let instance = Arc::clone(self.instance());
+        let config = &self.config.prefetch;
+
+        let mut mode = self.mode.clone();
+        if config.next_page && mode.next_page() && !self.is_page_cached(mode.get_page()) {
+            self.prefetch_queue
+                .push_back(Prefetch::Page(Arc::clone(&instance), mode));
+        }
+
+        for post in self.posts.iter().take(config.post_details) {
+            if post.comment_count > 0 && post.comments.is_empty() {
+                self.prefetch_queue.push_back(Prefetch::PostDetails(
+                    Arc::clone(&instance),
+                    post.short_id.clone(),
+                ));
+            }
+        }
+    }
+
+    /// Request planned prefetches, as far as the concurrency limit and byte
+    /// budget allow.
+    pub(super) fn request_prefetches(
+        &mut self,
+        tx_posts: &Sender<PostsRequest>,
+        tx_details: &Sender<DetailsRequest>,
+    ) -> Result<()> {
+        let config = &self.config.prefetch;
+
+        while self.prefetching.load(Ordering::Relaxed) < config.concurrency {
+            let Some(prefetch) = self.prefetch_queue.pop_front() else {
+                break;
+            };
+
+            let (Prefetch::Page(instance, _) | Prefetch::PostDetails(instance, _)) = &prefetch;
+            if !self.is_current_instance(instance) {
+                continue;
+            }
+            if config.byte_budget > 0 && instance.downloaded.load().prefetched >= config.byte_budget
+            {
+                self.prefetch_queue.clear();
+                break;
+            }
+
+            self.prefetching.fetch_add(1, Ordering::Relaxed);
+            match prefetch {
+                Prefetch::Page(instance, mode) => {
+                    self.prefetching_pages.insert(mode.path());
+                    tx_posts
+                        .send((instance, mode, Purpose::Prefetch))
+                        .context("load posts channel is closed")?
+                }
+                Prefetch::PostDetails(instance, short_id) => tx_details
+                    .send((instance, short_id, Purpose::Prefetch))
+                    .context("load post details channel is closed")?,
+            }
+        }
+
+        Ok(())
+    }
+}
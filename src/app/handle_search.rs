@@ -0,0 +1,376 @@
+//! Incremental search - list only the posts (or comments) matching what is
+//! typed after `/`, until the search is cleared.
+use std::mem;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::App;
+use crate::{data::{Post, PostComment}, utils::match_indices};
+
+/// Rows listed before searching, to list again once the search is cleared.
+#[derive(Debug)]
+enum Unfiltered {
+    Posts(Vec<Post>),
+    /// Comments listed by modes which list comments
+    Comments(Vec<PostComment>),
+    /// Comments on the post (with the given short ID) shown in the details
+    /// popup
+    Details(String, Vec<PostComment>),
+}
+
+/// Query being searched for, and the rows it filtered out.
+#[derive(Debug)]
+pub(super) struct Search {
+    pub query: String,
+    /// Whether the query is still being typed, rather than the matches browsed
+    pub is_typing: bool,
+    unfiltered: Unfiltered,
+}
+
+impl Search {
+    /// Query to highlight in the listed posts, if they are being searched.
+    pub fn posts_query(&self) -> Option<&str> {
+        matches!(self.unfiltered, Unfiltered::Posts(_)).then_some(self.query.as_str())
+    }
+}
+
+/// Rows which can be searched.
+trait Searchable: Clone {
+    /// Identifies the row, to keep it selected as rows are filtered
+    fn key(&self) -> &str;
+
+    fn matches(&self, query: &str) -> bool;
+}
+
+impl Searchable for Post {
+    fn key(&self) -> &str {
+        &self.short_id
+    }
+
+    /// Whether the title, submitter, a tag or the domain contain the query, or
+    /// the title fuzzy matches it.
+    fn matches(&self, query: &str) -> bool {
+        let contains = |text: &str| match_indices(query, text, false).is_some();
+
+        match_indices(query, &self.title, true).is_some()
+            || contains(&self.submitter_user)
+            || self.tags.iter().any(|tag| contains(tag))
+            || self.domain().is_some_and(|domain| contains(&domain))
+    }
+}
+
+impl Searchable for PostComment {
+    fn key(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether the comment or its author contain the query - fuzzy matching
+    /// would match most comments.
+    fn matches(&self, query: &str) -> bool {
+        match_indices(query, &self.comment_plain, false).is_some()
+            || match_indices(query, &self.commenting_user, false).is_some()
+    }
+}
+
+/// Whether the row is listed while searching for the query - every row is
+/// until something is typed.
+fn is_match<T: Searchable>(row: &T, query: &str) -> bool {
+    query.is_empty() || row.matches(query)
+}
+
+fn position<T: Searchable>(rows: &[T], key: Option<String>) -> Option<usize> {
+    let key = key?;
+    rows.iter().position(|row| row.key() == key)
+}
+
+/// Keep only the rows matching the query. Returns every row, and the index of
+/// the selected row if it is still listed.
+fn filter_rows<T: Searchable>(
+    rows: &mut Vec<T>,
+    query: &str,
+    selected: Option<usize>,
+) -> (Vec<T>, Option<usize>) {
+    let selected = selected
+        .and_then(|i| rows.get(i))
+        .map(|r| r.key().to_owned());
+    let all = rows.clone();
+    rows.retain(|row| is_match(row, query));
+
+    (all, position(rows, selected))
+}
+
+/// List every row again. Rows listed while filtering are kept as they are now,
+/// as they could have been changed (e.g. marked read), and any removed (e.g.
+/// hidden) are left out. Returns the index of the selected row.
+fn unfilter_rows<T: Searchable>(
+    rows: &mut Vec<T>,
+    all: Vec<T>,
+    query: &str,
+    selected: Option<usize>,
+) -> Option<usize> {
+    let selected = selected
+        .and_then(|i| rows.get(i))
+        .map(|r| r.key().to_owned());
+    let mut listed = mem::take(rows);
+    *rows = all
+        .into_iter()
+        .filter_map(
+            |row| match listed.iter().position(|r| r.key() == row.key()) {
+                Some(i) => Some(listed.swap_remove(i)),
+                None if is_match(&row, query) => None,
+                None => Some(row),
+            },
+        )
+        .collect();
+
+    position(rows, selected)
+}
+
+impl App {
+    /// Start typing a search of the listed posts (or comments), or of the
+    /// comments in the details popup if it is open. Carries on typing the
+    /// current search if it is of the same rows.
+    pub(super) fn start_search(&mut self) {
+        let details = self
+            .show_details_popup
+            .then(|| self.current_post().map(|p| p.short_id.clone()))
+            .flatten();
+
+        if let Some(search) = &mut self.search {
+            let is_same = match &search.unfiltered {
+                Unfiltered::Details(id, _) => details.as_ref() == Some(id),
+                Unfiltered::Posts(_) | Unfiltered::Comments(_) => details.is_none(),
+            };
+            if is_same {
+                search.is_typing = true;
+                return;
+            }
+            self.clear_search();
+        }
+
+        let unfiltered = match details {
+            Some(id) => Unfiltered::Details(id, Vec::new()),
+            None if self.mode.lists_comments() => Unfiltered::Comments(Vec::new()),
+            None => Unfiltered::Posts(Vec::new()),
+        };
+        self.resume_search(Some(Search {
+            query: String::new(),
+            is_typing: true,
+            unfiltered,
+        }));
+    }
+
+    /// List every row again.
+    pub(super) fn clear_search(&mut self) {
+        self.suspend_search();
+    }
+
+    /// Whether the comments in the details popup are being searched.
+    pub(super) fn searches_details(&self) -> bool {
+        self.search
+            .as_ref()
+            .is_some_and(|s| matches!(s.unfiltered, Unfiltered::Details(..)))
+    }
+
+    /// Number of rows matching the search.
+    pub(super) fn search_matches(&self) -> usize {
+        match self.search.as_ref().map(|s| &s.unfiltered) {
+            Some(Unfiltered::Details(..)) => self.current_post().map_or(0, |p| p.comments.len()),
+            _ => self.list_len(),
+        }
+    }
+
+    /// Stop filtering the rows, listing every one of them again, e.g. to
+    /// replace them. Returns the search, to filter the rows with it again.
+    pub(super) fn suspend_search(&mut self) -> Option<Search> {
+        let mut search = self.search.take()?;
+        let query = search.query.as_str();
+
+        match &mut search.unfiltered {
+            Unfiltered::Posts(all) => {
+                let selected = self.posts_list_state.selected();
+                let selected = unfilter_rows(&mut self.posts, mem::take(all), query, selected);
+                self.select_searched_row(selected);
+            }
+            Unfiltered::Comments(all) => {
+                let selected = self.posts_list_state.selected();
+                let selected = unfilter_rows(&mut self.comments, mem::take(all), query, selected);
+                self.select_searched_row(selected);
+            }
+            Unfiltered::Details(id, all) => {
+                if let Some(post) = self.posts.iter_mut().find(|p| p.short_id == *id) {
+                    let selected = self.comments_list_state.selected();
+                    let selected =
+                        unfilter_rows(&mut post.comments, mem::take(all), query, selected);
+                    self.comments_list_state.select(selected.or(Some(0)));
+                }
+            }
+        }
+
+        Some(search)
+    }
+
+    /// Filter the rows with the (suspended) search. It is dropped if the rows
+    /// it was searching are no longer shown.
+    pub(super) fn resume_search(&mut self, search: Option<Search>) {
+        let Some(mut search) = search else {
+            return;
+        };
+        let query = search.query.as_str();
+        let lists_comments = self.mode.lists_comments();
+
+        match &mut search.unfiltered {
+            Unfiltered::Posts(all) if !lists_comments => {
+                let selected = self.posts_list_state.selected();
+                let (rows, selected) = filter_rows(&mut self.posts, query, selected);
+                *all = rows;
+                self.select_searched_row(selected);
+            }
+            Unfiltered::Comments(all) if lists_comments => {
+                let selected = self.posts_list_state.selected();
+                let (rows, selected) = filter_rows(&mut self.comments, query, selected);
+                *all = rows;
+                self.select_searched_row(selected);
+            }
+            Unfiltered::Details(id, all) if self.show_details_popup => {
+                let Some(post) = self.posts.iter_mut().find(|p| p.short_id == *id) else {
+                    return;
+                };
+                let selected = self.comments_list_state.selected();
+                let (rows, selected) = filter_rows(&mut post.comments, query, selected);
+                *all = rows;
+                let first = (!post.comments.is_empty()).then_some(0);
+                self.comments_list_state.select(selected.or(first));
+            }
+            _ => return,
+        }
+
+        self.search = Some(search);
+    }
+
+    /// Select the row at the given index in the main list, or the first row if
+    /// the one selected is no longer listed. Nothing is selected if no rows
+    /// match.
+    fn select_searched_row(&mut self, index: Option<usize>) {
+        match index {
+            Some(index) => self.select_row(index),
+            None if self.list_len() == 0 => self.posts_list_state.select(None),
+            None => self.select_row(0),
+        }
+    }
+
+    /// Change the query, filtering the rows again.
+    fn edit_query(&mut self, edit: impl FnOnce(&mut String)) {
+        let mut search = self.suspend_search();
+        if let Some(search) = &mut search {
+            edit(&mut search.query);
+        }
+        self.resume_search(search);
+    }
+
+    /// Handle a key pressed while the search query is being typed.
+    pub(super) fn handle_search_key(&mut self, ev: KeyEvent) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        let is_ctrl = ev.modifiers.contains(KeyModifiers::CONTROL);
+
+        match ev.code {
+            KeyCode::Esc => self.clear_search(),
+            KeyCode::Char('c') if is_ctrl => self.clear_search(),
+            KeyCode::Enter if search.query.is_empty() => self.clear_search(),
+            KeyCode::Enter => search.is_typing = false,
+            KeyCode::Backspace if search.query.is_empty() => self.clear_search(),
+            KeyCode::Backspace => self.edit_query(|query| {
+                query.pop();
+            }),
+            KeyCode::Down => self.next_row(),
+            KeyCode::Char('n') if is_ctrl => self.next_row(),
+            KeyCode::Up => self.previous_row(),
+            KeyCode::Char('p') if is_ctrl => self.previous_row(),
+            KeyCode::Char(c) if !is_ctrl => self.edit_query(|query| query.push(c)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use pretty_assertions::assert_eq;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::{app::command::{Channels, Command, test::app}, data::test_post};
+
+    fn listed(app: &App) -> Vec<&str> {
+        app.posts.iter().map(|p| p.short_id.as_str()).collect()
+    }
+
+    fn press(app: &mut App, tx: &Channels, code: KeyCode) {
+        app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE), tx)
+            .unwrap();
+    }
+
+    #[test]
+    fn filter_and_clear() {
+        let (mut app, tx, _rx) = app();
+        app.dispatch(Command::Select(2), &tx).unwrap();
+
+        press(&mut app, &tx, KeyCode::Char('/'));
+        assert_eq!(listed(&app), ["a", "b", "c"]);
+        press(&mut app, &tx, KeyCode::Char('B'));
+        assert_eq!(listed(&app), ["b"]);
+        assert_eq!(app.posts_list_state.selected(), Some(0));
+        press(&mut app, &tx, KeyCode::Char('x'));
+        assert_eq!(listed(&app), Vec::<&str>::new());
+        assert_eq!(app.posts_list_state.selected(), None);
+        press(&mut app, &tx, KeyCode::Backspace);
+        press(&mut app, &tx, KeyCode::Enter);
+        assert_eq!(app.search.as_ref().unwrap().is_typing, false);
+
+        // Changes to the matches are kept
+        app.dispatch(Command::Save, &tx).unwrap();
+        press(&mut app, &tx, KeyCode::Esc);
+        assert!(app.search.is_none());
+        assert_eq!(listed(&app), ["a", "b", "c"]);
+        assert!(app.posts[1].is_saved);
+        assert_eq!(app.posts_list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn hidden_while_searching() {
+        let (mut app, tx, _rx) = app();
+        app.start_search();
+        app.edit_query(|query| query.push_str("post"));
+        app.dispatch(Command::NextMatch, &tx).unwrap();
+        app.dispatch(Command::Hide, &tx).unwrap();
+        assert_eq!(listed(&app), ["a", "c"]);
+
+        // Typing again
+        app.dispatch(Command::Search, &tx).unwrap();
+        app.edit_query(|query| query.push_str(" c"));
+        assert_eq!(listed(&app), ["c"]);
+
+        app.clear_search();
+        assert_eq!(listed(&app), ["a", "c"]);
+
+        // Cleared when the listing changes
+        app.start_search();
+        app.edit_query(|query| query.push('a'));
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert!(app.search.is_none());
+    }
+
+    #[test]
+    fn list_clear_of_search_bar() {
+        let (mut app, tx, _rx) = app();
+        app.replace_posts((0..20).map(|i| test_post(&i.to_string())).collect());
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+
+        app.dispatch(Command::Search, &tx).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        // The search bar covers the 3 rows above the bottom border
+        assert!(app.table_ends_at < 30 - 1 - 3);
+    }
+}
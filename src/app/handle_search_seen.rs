@@ -0,0 +1,170 @@
+//! Searching everything seen - find posts and comments fetched in any session
+//! by searching the full-text index of them, and open them.
+use std::sync::mpsc::Sender;
+
+use color_eyre::{Result, eyre::Context};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::ListState;
+
+use super::{App, action::DatabaseAction};
+use crate::database::{get_db_connection, queries::{SearchHit, Seen, search_seen}};
+
+/// Maximum number of results listed.
+const MAX_HITS: usize = 50;
+
+/// Query typed into the Search view, and the posts and comments found.
+#[derive(Debug, Default)]
+pub(super) struct SearchSeen {
+    pub query: String,
+    pub hits: Vec<SearchHit>,
+    pub list_state: ListState,
+}
+
+impl App {
+    /// Open the Search view, searching for the given query.
+    pub(super) fn open_search_seen(&mut self, query: String) -> Result<()> {
+        self.search_seen = Some(SearchSeen {
+            query,
+            ..Default::default()
+        });
+        self.update_search_seen()
+    }
+
+    /// Search for the query again, selecting the best match.
+    fn update_search_seen(&mut self) -> Result<()> {
+        let Some(search) = &mut self.search_seen else {
+            return Ok(());
+        };
+
+        let conn = get_db_connection(&self.db)?;
+        let base_url = &self.instances[self.instance].base_url;
+        search.hits = search_seen(&conn, base_url, &search.query, MAX_HITS)?;
+        search
+            .list_state
+            .select((!search.hits.is_empty()).then_some(0));
+
+        Ok(())
+    }
+
+    /// Open the selected post or comment. Posts are marked read.
+    fn open_search_hit(&mut self, tx: &Sender<DatabaseAction>) -> Result<()> {
+        let Some(search) = &self.search_seen else {
+            return Ok(());
+        };
+        let Some(hit) = search
+            .list_state
+            .selected()
+            .and_then(|i| search.hits.get(i))
+        else {
+            return Ok(());
+        };
+
+        match &hit.seen {
+            Seen::Comment(comment) => {
+                open::that_detached(&comment.url).context("failed to launch link opener")
+            }
+            // Listed, so it is shown as read straight away
+            Seen::Post(post) => match self.posts.iter().position(|p| p.short_id == post.short_id) {
+                Some(index) => self.open_post(index, tx),
+                None => {
+                    let url = if post.url.is_empty() {
+                        &post.comments_url
+                    } else {
+                        &post.url
+                    };
+                    open::that_detached(url).context("failed to launch link opener")?;
+
+                    tx.send(DatabaseAction::MarkPostRead {
+                        instance: self.instance().base_url.clone(),
+                        post: Box::new(post.clone()),
+                    })
+                    .context("mark post read channel is closed")
+                }
+            },
+        }
+    }
+
+    /// Handle a key pressed while the Search view is open.
+    pub(super) fn handle_search_seen_key(
+        &mut self,
+        ev: KeyEvent,
+        tx: &Sender<DatabaseAction>,
+    ) -> Result<()> {
+        let Some(search) = &mut self.search_seen else {
+            return Ok(());
+        };
+        let is_ctrl = ev.modifiers.contains(KeyModifiers::CONTROL);
+
+        match ev.code {
+            KeyCode::Esc => self.search_seen = None,
+            KeyCode::Char('c') if is_ctrl => self.search_seen = None,
+            KeyCode::Enter => self.open_search_hit(tx)?,
+            KeyCode::Down => search.list_state.select_next(),
+            KeyCode::Char('n') if is_ctrl => search.list_state.select_next(),
+            KeyCode::Up => search.list_state.select_previous(),
+            KeyCode::Char('p') if is_ctrl => search.list_state.select_previous(),
+            KeyCode::Backspace => {
+                search.query.pop();
+                self.update_search_seen()?;
+            }
+            KeyCode::Char(c) if !is_ctrl => {
+                search.query.push(c);
+                self.update_search_seen()?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{app::command::{Command, test::app}, database::queries::index_posts};
+
+    /// Short IDs of the posts (or URLs of the comments) found, and the index
+    /// of the one selected.
+    fn found(app: &App) -> (Vec<&str>, Option<usize>) {
+        let search = app.search_seen.as_ref().unwrap();
+        let mut ids: Vec<&str> = search
+            .hits
+            .iter()
+            .map(|hit| match &hit.seen {
+                Seen::Post(post) => post.short_id.as_str(),
+                Seen::Comment(comment) => comment.url.as_str(),
+            })
+            .collect();
+        // Equally good matches are in no particular order
+        ids.sort();
+        (ids, search.list_state.selected())
+    }
+
+    #[test]
+    fn search_and_close() {
+        let (mut app, tx, _rx) = app();
+        let conn = get_db_connection(&app.db).unwrap();
+        index_posts(&conn, &app.instance().base_url, &app.posts).unwrap();
+        drop(conn);
+
+        app.dispatch(Command::SearchSeen(String::from("post")), &tx)
+            .unwrap();
+        assert_eq!(found(&app), (vec!["a", "b", "c"], Some(0)));
+
+        for code in [KeyCode::Char(' '), KeyCode::Char('b')] {
+            app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE), &tx)
+                .unwrap();
+        }
+        assert_eq!(found(&app), (vec!["b"], Some(0)));
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE), &tx)
+            .unwrap();
+        assert_eq!(found(&app), (vec![], None));
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE), &tx)
+            .unwrap();
+        assert!(app.search_seen.is_none());
+    }
+}
@@ -1,22 +1,102 @@
 mod action;
+mod command;
 mod display;
 mod handle_cache;
 mod handle_events;
+mod handle_filters;
+mod handle_hidden;
+mod handle_instances;
+mod handle_palette;
 mod handle_posts;
+mod handle_prefetch;
+mod handle_search;
+mod handle_search_seen;
 mod navigate;
+mod retry;
 mod run;
+#[cfg(test)]
+mod stub_server;
 
-use std::{collections::HashMap, sync::{Arc, atomic::{AtomicBool, AtomicU32}}, time::Duration};
+use std::{collections::{HashMap, HashSet, VecDeque}, sync::{Arc, atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering}}, time::Duration};
 
+use chrono::{DateTime, Utc};
 use color_eyre::Result;
+use handle_instances::InstanceState;
+use handle_palette::Palette;
+pub use handle_palette::PaletteEntry;
+use handle_prefetch::Prefetch;
+use handle_search::Search;
+use handle_search_seen::SearchSeen;
 use ratatui::widgets::{ListState, ScrollbarState};
 use reqwest::blocking::{Client, ClientBuilder};
 
-use crate::{config::Config, data::Post, database::DbPool, modes::Mode};
+use crate::{config::Config, data::{Bandwidth, BandwidthTotals, Post, PostComment}, database::DbPool, modes::Mode, panic};
 
 pub const TABLE_ROW_HEIGHT: usize = 2;
 
-pub type DownloadedBytes = Arc<AtomicU32>;
+/// An instance of the lobste.rs codebase which can be browsed.
+#[derive(Debug)]
+pub struct Instance {
+    pub name: String,
+    pub base_url: String,
+    pub downloaded: Downloaded,
+}
+
+impl Instance {
+    pub fn new(name: String, base_url: String) -> Self {
+        Self {
+            name,
+            base_url,
+            downloaded: Default::default(),
+        }
+    }
+}
+
+/// Bytes downloaded from an instance in this session.
+#[derive(Debug, Default)]
+pub struct Downloaded {
+    listings: AtomicU64,
+    comments: AtomicU64,
+    prefetched: AtomicU64,
+}
+
+impl Downloaded {
+    pub fn add(&self, bandwidth: &Bandwidth) {
+        self.listings
+            .fetch_add(bandwidth.listings, Ordering::Relaxed);
+        self.comments
+            .fetch_add(bandwidth.comments, Ordering::Relaxed);
+        self.prefetched
+            .fetch_add(bandwidth.prefetched, Ordering::Relaxed);
+    }
+
+    pub fn load(&self) -> Bandwidth {
+        Bandwidth {
+            listings: self.listings.load(Ordering::Relaxed),
+            comments: self.comments.load(Ordering::Relaxed),
+            prefetched: self.prefetched.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Numbers of posts in the results for a listing which were hidden, filtered
+/// out by their tags (whether dropped or dimmed), or dropped by filter rules.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ListingCounts {
+    pub hidden: usize,
+    pub filtered: usize,
+    pub ruled_out: usize,
+}
+
+/// A post which has been hidden, and where it was listed.
+#[derive(Debug)]
+struct HiddenPost {
+    instance: Arc<Instance>,
+    /// Path of the listing it was hidden from
+    path: String,
+    index: usize,
+    post: Post,
+}
 
 #[derive(Debug)]
 pub struct App {
@@ -25,9 +105,32 @@ pub struct App {
     db: Arc<DbPool>,
     mode: Mode,
 
+    instances: Vec<Arc<Instance>>,
+    /// Index of the instance currently being browsed
+    instance: usize,
+    /// Stored state for each instance which is not currently being browsed
+    instance_states: Vec<Option<InstanceState>>,
+
     posts: Vec<Post>,
+    /// Comments listed by modes which list comments rather than posts
+    comments: Vec<PostComment>,
     cache_posts: Vec<Vec<Post>>,
-    cache_modes: HashMap<String, Vec<Vec<Post>>>,
+    /// Pages of each mode switched away from, keyed by its display name, along
+    /// with the mode itself
+    cache_modes: HashMap<String, (Mode, Vec<Vec<Post>>)>,
+    /// Comments listed by modes which list comments, for each listing (keyed
+    /// by its path) which was switched away from
+    cache_comments: HashMap<String, Vec<PostComment>>,
+    /// When the results for each listing (keyed by its path) were last fetched,
+    /// or last requested to be refreshed
+    refreshed_at: HashMap<String, DateTime<Utc>>,
+    /// Number of posts hidden or filtered out in the results for each listing
+    /// (keyed by its path)
+    listing_counts: HashMap<String, ListingCounts>,
+    /// Keep hidden posts in listings, rather than dropping them
+    show_hidden: Arc<AtomicBool>,
+    /// Post which was hidden last, so hiding it can be undone
+    last_hidden: Option<HiddenPost>,
     posts_list_state: ListState,
     comments_list_state: ListState,
     posts_scroll_state: ScrollbarState,
@@ -37,11 +140,42 @@ pub struct App {
 
     show_keybinds_popup: bool,
     show_details_popup: bool,
+    /// Show which filter rules match the selected post
+    show_filters_popup: bool,
+    /// Command palette, if open
+    palette: Option<Palette>,
+    /// Search filtering the rows listed, if any
+    search: Option<Search>,
+    /// Search view, finding posts and comments seen in any session, if open
+    search_seen: Option<SearchSeen>,
+    /// Index of the tag selected in the details popup's Tags row
+    selected_tag: Option<usize>,
+    /// Totals for the current instance shown in the stats popup, loaded when
+    /// it is opened
+    stats_popup: Option<BandwidthTotals>,
+    /// Mode (and page) browsed before switching to one still being loaded,
+    /// along with the row selected in it, to go back to if loading fails
+    fallback_mode: Option<(Mode, Option<usize>)>,
+    /// Error from the last failed fetch, shown until dismissed or retried
+    fetch_error: Option<String>,
+    /// Attempts left for the posts being loaded, if the request is being
+    /// retried
+    posts_attempts_left: Option<u32>,
+    /// Attempts left for the post details being loaded, if the request is being
+    /// retried
+    details_attempts_left: Option<u32>,
+
+    /// Prefetches to request once earlier ones have been fetched
+    prefetch_queue: VecDeque<Prefetch>,
+    /// Number of prefetch requests waiting to be fetched or being fetched
+    prefetching: Arc<AtomicUsize>,
+    /// Pages being prefetched, to wait for rather than fetch again if browsed
+    /// in the meantime
+    prefetching_pages: HashSet<String>,
 
     is_loading: Arc<AtomicBool>,
     is_loading_comments: Arc<AtomicBool>,
     is_running: Arc<AtomicBool>,
-    downloaded: DownloadedBytes,
 
     pub exit_code: i32,
 }
@@ -49,7 +183,7 @@ pub struct App {
 static USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "-", env!("CARGO_PKG_VERSION"),);
 
 impl App {
-    pub fn new(db: DbPool, config: Config) -> Result<Self> {
+    pub fn new(db: DbPool, mut config: Config) -> Result<Self> {
         let client = ClientBuilder::default()
             .timeout(Duration::from_secs(20))
             .user_agent(USER_AGENT)
@@ -58,30 +192,74 @@ impl App {
 
         let mode = config.default_mode.clone();
 
+        // Starting in a tag mode - include it in the mode cycle
+        if let Mode::Tag(tags, _) = &mode
+            && config.tags.is_empty()
+        {
+            config.tags = tags.clone();
+        }
+        // Starting in the top mode - keep the same period when cycling back to it
+        if let Mode::Top { period, .. } = &mode {
+            config.top_period = *period;
+        }
+        // Likewise for the order of saved posts
+        if let Mode::Saved { sort, .. } = &mode {
+            config.saved_sort = *sort;
+        }
+
+        let (instances, instance) = config.get_instances();
+        let instances: Vec<Arc<Instance>> = instances
+            .into_iter()
+            .map(|i| Arc::new(Instance::new(i.name, i.base_url)))
+            .collect();
+        panic::set_instance(&instances[instance].base_url);
+
         Ok(App {
             client: client.into(),
             db: db.into(),
             config,
             mode,
 
+            instance_states: instances.iter().map(|_| None).collect(),
+            instances,
+            instance,
+
             is_running: AtomicBool::new(true).into(),
             is_loading: AtomicBool::new(true).into(),
             posts_list_state: ListState::default().with_selected(Some(0)),
             posts_scroll_state: ScrollbarState::new(25 * TABLE_ROW_HEIGHT),
             comments_list_state: ListState::default().with_selected(Some(0)),
-            // Capacity only needs to cover the available modes
-            cache_modes: HashMap::with_capacity(3),
+            // Capacity only needs to cover the modes in the cycle
+            cache_modes: HashMap::with_capacity(5),
 
             show_keybinds_popup: Default::default(),
             show_details_popup: Default::default(),
+            show_filters_popup: Default::default(),
+            palette: Default::default(),
+            search: Default::default(),
+            search_seen: Default::default(),
+            stats_popup: Default::default(),
+            fallback_mode: Default::default(),
+            fetch_error: Default::default(),
+            posts_attempts_left: Default::default(),
+            details_attempts_left: Default::default(),
             is_loading_comments: Default::default(),
             exit_code: Default::default(),
             posts: Default::default(),
+            comments: Default::default(),
             cache_posts: Default::default(),
+            cache_comments: Default::default(),
+            refreshed_at: Default::default(),
+            listing_counts: Default::default(),
+            selected_tag: Default::default(),
+            show_hidden: Default::default(),
+            last_hidden: Default::default(),
+            prefetch_queue: Default::default(),
+            prefetching: Default::default(),
+            prefetching_pages: Default::default(),
             prev_size: Default::default(),
             table_starts_at: Default::default(),
             table_ends_at: Default::default(),
-            downloaded: Default::default(),
         })
     }
 }
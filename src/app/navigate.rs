@@ -1,13 +1,23 @@
-use std::sync::mpsc::Sender;
+use std::sync::{atomic::Ordering, mpsc::Sender};
 
 use color_eyre::Result;
 
-use super::{App, TABLE_ROW_HEIGHT};
-use crate::modes::Mode;
+use super::{App, TABLE_ROW_HEIGHT, action::PostsRequest};
+use crate::modes::{Mode, STARTING_PAGE};
 
 impl App {
+    /// Number of items in the main list - posts, or comments for modes which
+    /// list comments.
+    pub(super) fn list_len(&self) -> usize {
+        if self.mode.lists_comments() {
+            self.comments.len()
+        } else {
+            self.posts.len()
+        }
+    }
+
     pub(super) fn first_row(&mut self) {
-        if self.posts.is_empty() {
+        if self.list_len() == 0 {
             return;
         }
 
@@ -21,7 +31,7 @@ impl App {
     }
 
     pub(super) fn last_row(&mut self) {
-        if self.posts.is_empty() {
+        if self.list_len() == 0 {
             return;
         }
 
@@ -30,14 +40,11 @@ impl App {
             return;
         }
 
-        self.posts_list_state.select_last();
-        self.posts_scroll_state = self
-            .posts_scroll_state
-            .position(self.posts.len() * TABLE_ROW_HEIGHT);
+        self.select_row(self.list_len() - 1);
     }
 
     pub(super) fn next_row(&mut self) {
-        if self.posts.is_empty() {
+        if self.list_len() == 0 {
             return;
         }
 
@@ -48,7 +55,7 @@ impl App {
 
         let i = match self.posts_list_state.selected() {
             Some(i) => {
-                if i >= self.posts.len() - 1 {
+                if i >= self.list_len() - 1 {
                     0
                 } else {
                     i + 1
@@ -56,85 +63,262 @@ impl App {
             }
             None => 0,
         };
-        self.posts_list_state.select(Some(i));
-        self.posts_scroll_state = self.posts_scroll_state.position(i * TABLE_ROW_HEIGHT);
+        self.select_row(i);
     }
 
     pub(super) fn previous_row(&mut self) {
-        if self.posts.is_empty() {
+        if self.list_len() == 0 {
             return;
         }
 
+        // Moving up from the first comment selects the post itself again
         if self.show_details_popup {
-            self.comments_list_state.select_previous();
+            match self.comments_list_state.selected() {
+                Some(0) | None => self.comments_list_state.select(None),
+                Some(_) => self.comments_list_state.select_previous(),
+            }
             return;
         }
 
         let i = match self.posts_list_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    self.posts.len() - 1
+                    self.list_len() - 1
                 } else {
                     i - 1
                 }
             }
             None => 0,
         };
-        self.posts_list_state.select(Some(i));
-        self.posts_scroll_state = self.posts_scroll_state.position(i * TABLE_ROW_HEIGHT);
+        self.select_row(i);
+    }
+
+    /// Select the row at the given index in the main list, scrolling it into
+    /// view.
+    pub(super) fn select_row(&mut self, index: usize) {
+        self.posts_list_state.select(Some(index));
+        self.posts_scroll_state = self.posts_scroll_state.position(index * TABLE_ROW_HEIGHT);
+    }
+
+    /// Show the posts (or comments) stored for the mode (or page) switched
+    /// to, otherwise load them, going back to the mode switched from (given
+    /// with its selected row) if loading fails.
+    fn show_switched_mode(
+        &mut self,
+        is_cached: bool,
+        prev: (Mode, Option<usize>),
+        tx: &Sender<PostsRequest>,
+    ) -> Result<()> {
+        if is_cached {
+            self.fallback_mode = None;
+            self.is_loading.store(false, Ordering::Relaxed);
+            self.plan_prefetch();
+            return Ok(());
+        }
+
+        // Switched again before anything was listed - keep going back to what
+        // was listed last
+        let (_, selected) = prev;
+        if selected.is_some() {
+            self.fallback_mode = Some(prev);
+        }
+
+        // Already being prefetched - wait for it rather than fetching it again
+        if self.prefetching_pages.contains(&self.mode.path()) {
+            self.is_loading.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+        self.load_posts(tx)
+    }
+
+    /// Go back to the mode (and page) browsed before switching to one which
+    /// failed to load, listing what was listed before again.
+    pub(super) fn return_to_fallback_mode(&mut self) {
+        let Some((mode, selected)) = self.fallback_mode.take() else {
+            return;
+        };
+
+        self.store_mode();
+        self.mode = mode;
+        self.load_mode();
+        match selected {
+            Some(index) if index < self.list_len() => self.select_row(index),
+            _ => self.first_row(),
+        }
+    }
+
+    /// Mode being browsed, along with the selected row if any are listed.
+    fn current_mode(&self) -> (Mode, Option<usize>) {
+        let selected = self.posts_list_state.selected();
+        (self.mode.clone(), selected.filter(|_| self.list_len() > 0))
+    }
+
+    /// Whether nothing was listed for the page being browsed, not even posts
+    /// which were since hidden or filtered out.
+    fn is_listing_empty(&self) -> bool {
+        let counts = self.listing_counts();
+        self.list_len() == 0 && counts.hidden + counts.filtered + counts.ruled_out == 0
     }
 
-    pub(super) fn next_page(&mut self, tx: &Sender<Mode>) -> Result<()> {
+    pub(super) fn next_page(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
         // Wait to load the current posts - or there are no more posts so don't proceed
-        if self.posts.is_empty() {
+        if self.is_loading.load(Ordering::Relaxed) || self.is_listing_empty() {
             return Ok(());
         }
 
+        let prev = self.current_mode();
         if self.mode.next_page() {
             let page = self.mode.get_page();
             let prev_page = page - 1;
-            if !self.load_page(prev_page, page) {
-                self.load_posts(tx)?;
-            };
+            let is_cached = self.load_page(prev_page, page);
+            self.show_switched_mode(is_cached, prev, tx)?;
+
+            self.first_row();
+        }
+        Ok(())
+    }
+
+    /// Go to the given (1-indexed) page of the current mode.
+    pub(super) fn go_to_page(&mut self, page: u8, tx: &Sender<PostsRequest>) -> Result<()> {
+        let prev = self.current_mode();
+        let prev_page = self.mode.get_page();
+        if self.mode.set_page(page) {
+            let is_cached = self.load_page(prev_page, self.mode.get_page());
+            self.show_switched_mode(is_cached, prev, tx)?;
 
             self.first_row();
         }
         Ok(())
     }
 
-    pub(super) fn previous_page(&mut self, tx: &Sender<Mode>) -> Result<()> {
+    pub(super) fn previous_page(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        let prev = self.current_mode();
         if self.mode.prev_page() {
             let page = self.mode.get_page();
             let prev_page = page + 1;
-            if !self.load_page(prev_page, page) {
-                self.load_posts(tx)?;
-            };
+            let is_cached = self.load_page(prev_page, page);
+            self.show_switched_mode(is_cached, prev, tx)?;
 
             self.first_row();
         }
         Ok(())
     }
 
-    pub(super) fn next_mode(&mut self, tx: &Sender<Mode>) -> Result<()> {
+    /// Switch to the given mode, storing the current one in the cache.
+    pub(super) fn set_mode(&mut self, mode: Mode, tx: &Sender<PostsRequest>) -> Result<()> {
+        let prev = self.current_mode();
+        self.show_details_popup = false;
         self.first_row();
         self.store_mode();
-        self.mode.next_mode();
+        self.mode = mode;
+
+        // Cached - stop showing any request for the previous mode as loading.
+        // Local results may have changed since, so are always loaded again
+        let is_cached = !self.mode.is_local() && self.load_mode();
+        self.show_switched_mode(is_cached, prev, tx)
+    }
+
+    pub(super) fn next_mode(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        let mut mode = self.mode.clone();
+        mode.next_mode(&self.config.tags, self.config.top_period);
+        self.set_mode(mode, tx)
+    }
+
+    pub(super) fn prev_mode(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        let mut mode = self.mode.clone();
+        mode.prev_mode(&self.config.tags, self.config.top_period);
+        self.set_mode(mode, tx)
+    }
 
-        if !self.load_mode() {
-            self.load_posts(tx)?;
+    /// Cycle the period of the top mode, switching to the top mode if not
+    /// already in it.
+    pub(super) fn next_top_period(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        if let Mode::Top { period, .. } = self.mode {
+            self.config.top_period = period.next();
         }
 
-        Ok(())
+        let mode = Mode::Top {
+            period: self.config.top_period,
+            page: STARTING_PAGE,
+        };
+        self.set_mode(mode, tx)
     }
-    pub(super) fn prev_mode(&mut self, tx: &Sender<Mode>) -> Result<()> {
-        self.first_row();
-        self.store_mode();
-        self.mode.prev_mode();
 
-        if !self.load_mode() {
-            self.load_posts(tx)?;
+    /// Switch to the domain mode for the domain of the post at the given index.
+    pub(super) fn open_domain(&mut self, index: usize, tx: &Sender<PostsRequest>) -> Result<()> {
+        let Some(domain) = self.posts.get(index).and_then(|p| p.domain()) else {
+            return Ok(());
+        };
+
+        // Already browsing this domain
+        if matches!(&self.mode, Mode::Domain(d, _) if *d == domain) {
+            return Ok(());
         }
 
-        Ok(())
+        self.set_mode(Mode::Domain(domain, STARTING_PAGE), tx)
+    }
+
+    /// Switch to the mode listing stories submitted by the selected user.
+    pub(super) fn open_user_stories(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        let Some(user) = self.selected_user() else {
+            return Ok(());
+        };
+
+        let mode = Mode::UserStories(user.to_owned(), STARTING_PAGE);
+        if mode == self.mode {
+            return Ok(());
+        }
+
+        self.set_mode(mode, tx)
+    }
+
+    /// Switch to the mode listing comments written by the selected user.
+    pub(super) fn open_user_threads(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        let Some(user) = self.selected_user() else {
+            return Ok(());
+        };
+
+        let mode = Mode::UserThreads(user.to_owned(), STARTING_PAGE);
+        if mode == self.mode {
+            return Ok(());
+        }
+
+        self.set_mode(mode, tx)
+    }
+
+    /// Switch to the mode listing saved posts, in the configured order.
+    pub(super) fn open_saved(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        if let Mode::Saved { .. } = self.mode {
+            return Ok(());
+        }
+
+        let mode = Mode::Saved {
+            sort: self.config.saved_sort,
+            page: STARTING_PAGE,
+        };
+        self.set_mode(mode, tx)
+    }
+
+    /// Cycle the order of the saved posts, switching to them if not already
+    /// browsing them.
+    pub(super) fn next_saved_sort(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        if let Mode::Saved { sort, .. } = self.mode {
+            self.config.saved_sort = sort.next();
+        }
+
+        let mode = Mode::Saved {
+            sort: self.config.saved_sort,
+            page: STARTING_PAGE,
+        };
+        self.set_mode(mode, tx)
+    }
+
+    /// Switch to the mode listing posts which have been read.
+    pub(super) fn open_history(&mut self, tx: &Sender<PostsRequest>) -> Result<()> {
+        if let Mode::History(_) = self.mode {
+            return Ok(());
+        }
+
+        self.set_mode(Mode::History(STARTING_PAGE), tx)
     }
 }
@@ -0,0 +1,248 @@
+//! Retrying failed requests with jittered exponential backoff.
+
+use std::{collections::hash_map::RandomState, hash::{BuildHasher, Hasher}, sync::atomic::{AtomicBool, Ordering}, thread, time::{Duration, Instant}};
+
+use chrono::{DateTime, Utc};
+use reqwest::{StatusCode, blocking::{Client, Request, Response}, header::RETRY_AFTER};
+
+use crate::config::RetryConfig;
+
+/// Execute the request, retrying on connection errors and on 429 or 5xx
+/// responses until the configured number of attempts is used up.
+///
+/// `on_retry` is called with the number of attempts left before each retry.
+/// Once attempts are used up, or `is_running` is cleared while waiting to
+/// retry, the result of the last attempt is returned.
+pub(super) fn execute_with_retry(
+    client: &Client,
+    req: Request,
+    config: &RetryConfig,
+    is_running: &AtomicBool,
+    on_retry: impl Fn(u32),
+) -> reqwest::Result<Response> {
+    let attempts = config.attempts.max(1);
+
+    for attempt in 1..attempts {
+        // Requests with a streamed body can't be retried
+        let Some(retry_req) = req.try_clone() else {
+            break;
+        };
+
+        let res = client.execute(retry_req);
+        let delay = match &res {
+            Ok(resp) if is_retryable_status(resp.status()) => {
+                retry_after(resp).unwrap_or_else(|| backoff(config, attempt))
+            }
+            Err(e) if e.is_connect() || e.is_timeout() => backoff(config, attempt),
+            _ => return res,
+        };
+
+        if !is_running.load(Ordering::Relaxed) {
+            return res;
+        }
+        on_retry(attempts - attempt);
+        let delay = delay.min(Duration::from_millis(config.max_delay_ms));
+        if !sleep_while_running(delay, is_running) {
+            return res;
+        }
+    }
+
+    client.execute(req)
+}
+
+/// Sleep for the delay in short slices, so quitting isn't held up by a long
+/// wait. Returns whether still running.
+fn sleep_while_running(delay: Duration, is_running: &AtomicBool) -> bool {
+    let end = Instant::now() + delay;
+    loop {
+        if !is_running.load(Ordering::Relaxed) {
+            return false;
+        }
+        let left = end.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        thread::sleep(left.min(Duration::from_millis(50)));
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Delay requested by the server with a `Retry-After` header, given either in
+/// seconds or as a date.
+fn retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    // Dates in the past mean that the request can be retried straight away
+    Some((date.to_utc() - now).to_std().unwrap_or_default())
+}
+
+/// Delay before the given retry (starting from 1), doubling for each retry up
+/// to the maximum delay, with the upper half randomised to avoid retrying in
+/// lockstep with other clients.
+fn backoff(config: &RetryConfig, retry: u32) -> Duration {
+    let delay = config
+        .initial_delay_ms
+        .saturating_mul(1 << (retry - 1).min(32))
+        .min(config.max_delay_ms);
+
+    let half = delay / 2;
+    let jitter = RandomState::new().build_hasher().finish() % (half + 1);
+    Duration::from_millis(delay - half + jitter)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, net::TcpListener};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::app::stub_server::stub_server;
+
+    const CONFIG: RetryConfig = RetryConfig {
+        attempts: 3,
+        initial_delay_ms: 1,
+        max_delay_ms: 5,
+    };
+
+    fn get(client: &Client, url: &str) -> (reqwest::Result<Response>, Vec<u32>) {
+        let retries = RefCell::new(Vec::new());
+        let req = client.get(url).build().unwrap();
+        let is_running = AtomicBool::new(true);
+        let res = execute_with_retry(client, req, &CONFIG, &is_running, |left| {
+            retries.borrow_mut().push(left)
+        });
+
+        (res, retries.into_inner())
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let client = Client::new();
+        let (url, requests) = stub_server(&[
+            "503 Service Unavailable",
+            "429 Too Many Requests|Retry-After: 0\r\n",
+            "200 OK",
+        ]);
+
+        let (res, retries) = get(&client, &url);
+        assert_eq!(res.unwrap().status(), StatusCode::OK);
+        assert_eq!(retries, vec![2, 1]);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn gives_up_after_attempts() {
+        let client = Client::new();
+        let (url, requests) = stub_server(&[
+            "500 Internal Server Error",
+            "502 Bad Gateway",
+            "503 Service Unavailable",
+            "200 OK",
+        ]);
+
+        let (res, retries) = get(&client, &url);
+        assert_eq!(res.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(retries, vec![2, 1]);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn client_errors_not_retried() {
+        let client = Client::new();
+        let (url, requests) = stub_server(&["404 Not Found", "200 OK"]);
+
+        let (res, retries) = get(&client, &url);
+        assert_eq!(res.unwrap().status(), StatusCode::NOT_FOUND);
+        assert_eq!(retries, Vec::<u32>::new());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn quitting_stops_waiting() {
+        let client = Client::new();
+        let (url, requests) =
+            stub_server(&["503 Service Unavailable|Retry-After: 60\r\n", "200 OK"]);
+        let config = RetryConfig {
+            max_delay_ms: 60_000,
+            ..CONFIG
+        };
+        let is_running = AtomicBool::new(true);
+
+        let start = Instant::now();
+        let req = client.get(&url).build().unwrap();
+        let res = execute_with_retry(&client, req, &config, &is_running, |_| {
+            is_running.store(false, Ordering::Relaxed)
+        });
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(res.unwrap().status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn connect_errors_retried() {
+        // Nothing listening once the listener is dropped
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        let (res, retries) = get(&Client::new(), &url);
+        assert!(res.unwrap_err().is_connect());
+        assert_eq!(retries, vec![2, 1]);
+    }
+
+    #[test]
+    fn retry_after() {
+        let now = DateTime::parse_from_rfc3339("2025-01-01T12:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 01 Jan 2025 12:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 01 Jan 2025 11:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let config = RetryConfig {
+            attempts: 10,
+            initial_delay_ms: 100,
+            max_delay_ms: 1000,
+        };
+
+        for (retry, max) in [
+            (1, 100),
+            (2, 200),
+            (3, 400),
+            (4, 800),
+            (5, 1000),
+            (40, 1000),
+        ] {
+            let delay = backoff(&config, retry).as_millis() as u64;
+            assert!((max / 2..=max).contains(&delay), "retry {retry}: {delay}ms");
+        }
+    }
+}
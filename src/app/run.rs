@@ -1,18 +1,19 @@
-use std::{panic, sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::{Receiver, RecvTimeoutError, Sender, channel}}, thread::{self, JoinHandle}, time::Duration};
+use std::{collections::VecDeque, panic, sync::{Arc, atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc::{Receiver, RecvTimeoutError, Sender, channel}}, thread::{self, JoinHandle}, time::Duration};
 
+use chrono::Utc;
 use color_eyre::{Result, eyre::Context};
 use crossterm::event::{self, Event};
 use ratatui::DefaultTerminal;
 use reqwest::blocking::Client;
 
-use super::{App, DownloadedBytes, action::{Action, DatabaseAction}, handle_posts::{fetch_post_details, fetch_posts}};
-use crate::{database::{DbPool, get_db_connection, queries::{mark_post_read, mark_post_unread, update_posts}}, modes::Mode};
+use super::{App, Instance, action::{Action, DatabaseAction, DetailsRequest, Fetch, PostsRequest, Purpose, Request}, command::Channels, handle_cache::{load_listing, load_post_details}, handle_posts::{fetch_comments, fetch_post_details, fetch_posts}};
+use crate::{config::RetryConfig, database::{DbPool, get_db_connection, queries::{get_read_posts, get_saved_posts, hide_post, index_comments, index_posts, mark_post_read, mark_post_unread, save_post, unhide_post, unsave_post, update_comments, update_posts}}, modes::Mode};
 
 impl App {
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
         let (tx_actions, rx_actions) = channel::<Action>();
-        let (tx_load, rx_load) = channel::<Mode>();
-        let (tx_load_comments, rx_load_comments) = channel::<String>();
+        let (tx_load, rx_load) = channel::<PostsRequest>();
+        let (tx_load_comments, rx_load_comments) = channel::<DetailsRequest>();
         let (tx_db, rx_db) = channel::<DatabaseAction>();
 
         let mut handles = Vec::new();
@@ -26,43 +27,36 @@ impl App {
                 .spawn(move || on_thread_events(&is_running, tx_actions_clone))?,
         );
 
+        let fetcher = Fetcher {
+            db: Arc::clone(&self.db),
+            client: Arc::clone(&self.client),
+            retry: self.config.retry,
+            is_running: Arc::clone(&self.is_running),
+            offline: self.config.offline,
+            prefetching: Arc::clone(&self.prefetching),
+            show_hidden: Arc::clone(&self.show_hidden),
+        };
+
         // Handle fetching posts
         let is_running = Arc::clone(&self.is_running);
-        let db = Arc::clone(&self.db);
-        let downloaded = Arc::clone(&self.downloaded);
-        let client = Arc::clone(&self.client);
+        let fetcher_clone = fetcher.clone();
         let tx_actions_clone = tx_actions.clone();
         handles.push(
             thread::Builder::new()
                 .name("fetch_posts".into())
                 .spawn(move || {
-                    on_thread_posts(
-                        &is_running,
-                        db,
-                        client,
-                        rx_load,
-                        tx_actions_clone,
-                        downloaded,
-                    )
-                    .context("post fetching thread")
+                    on_thread_posts(&is_running, fetcher_clone, rx_load, tx_actions_clone)
+                        .context("post fetching thread")
                 })?,
         );
 
         // Handle loading post details
         let is_running = Arc::clone(&self.is_running);
-        let downloaded = Arc::clone(&self.downloaded);
-        let client = Arc::clone(&self.client);
         handles.push(
             thread::Builder::new()
                 .name("fetch_post_details".into())
                 .spawn(move || {
-                    on_thread_post_details(
-                        &is_running,
-                        client,
-                        rx_load_comments,
-                        tx_actions,
-                        downloaded,
-                    )
+                    on_thread_post_details(&is_running, fetcher, rx_load_comments, tx_actions)
                 })?,
         );
 
@@ -76,14 +70,12 @@ impl App {
         );
 
         // Run main thread
-        let mut res = self.main_loop(
-            terminal,
-            &tx_load,
-            &tx_load_comments,
-            &tx_db,
-            rx_actions,
-            &mut handles,
-        );
+        let channels = Channels {
+            posts: tx_load,
+            details: tx_load_comments,
+            db: tx_db,
+        };
+        let mut res = self.main_loop(terminal, &channels, rx_actions, &mut handles);
         self.is_running.store(false, Ordering::Relaxed);
 
         // Join and check for errors in other threads
@@ -107,9 +99,7 @@ impl App {
     fn main_loop(
         &mut self,
         terminal: &mut DefaultTerminal,
-        tx_load: &Sender<Mode>,
-        tx_load_details: &Sender<String>,
-        tx_db: &Sender<DatabaseAction>,
+        tx: &Channels,
         rx_actions: Receiver<Action>,
         handles: &mut Vec<JoinHandle<Result<()>>>,
     ) -> Result<()> {
@@ -117,7 +107,15 @@ impl App {
         terminal.draw(|frame| self.draw(frame))?;
 
         if self.posts.is_empty() {
-            self.load_posts(tx_load)?;
+            // Show the last known results straight away, refreshing them in the
+            // background
+            if self.load_cached_listing()? {
+                if !self.config.offline {
+                    self.refresh_posts(&tx.posts)?;
+                }
+            } else {
+                self.load_posts(&tx.posts)?;
+            }
         }
 
         while self.is_running.load(std::sync::atomic::Ordering::Relaxed) {
@@ -132,6 +130,9 @@ impl App {
                 }
             }
 
+            self.refresh_if_expired(&tx.posts)?;
+            self.request_prefetches(&tx.posts, &tx.details)?;
+
             let event = match rx_actions.recv_timeout(Duration::from_millis(50)) {
                 Ok(ev) => ev,
                 Err(e) => match e {
@@ -142,25 +143,85 @@ impl App {
             };
 
             match event {
-                Action::LoadPosts(posts) => {
-                    self.posts = posts;
+                Action::LoadPosts(instance, mode, mut posts, hidden)
+                    if self.is_current_instance(&instance) =>
+                {
+                    self.prefetching_pages.remove(&mode.path());
+                    self.refreshed_at.insert(mode.path(), Utc::now());
+                    self.filter_posts(&mode, &mut posts, hidden);
+
+                    // Loaded for a mode or page which is no longer being browsed -
+                    // keep the posts for when it is
+                    if mode != self.mode {
+                        self.store_page(&mode, posts);
+                        continue;
+                    }
+
+                    self.replace_posts(posts);
+                    self.fallback_mode = None;
+                    self.fetch_error = None;
+                    self.posts_attempts_left = None;
                     self.is_loading.store(false, Ordering::Relaxed);
-                    self.first_row();
+                    self.plan_prefetch();
                 }
-                Action::LoadPostDetails(post_details) => {
-                    if let Some(post) = self
+                Action::LoadComments(instance, mode, comments)
+                    if self.is_current_instance(&instance) && mode == self.mode =>
+                {
+                    self.refreshed_at.insert(mode.path(), Utc::now());
+                    self.replace_comments(comments);
+                    self.fallback_mode = None;
+                    self.fetch_error = None;
+                    self.posts_attempts_left = None;
+                    self.is_loading.store(false, Ordering::Relaxed);
+                }
+                Action::LoadPostDetails(instance, post_details)
+                    if self.is_current_instance(&instance) =>
+                {
+                    // Search the new comments instead
+                    let search = self.suspend_search();
+                    if let Some(index) = self
                         .posts
-                        .iter_mut()
-                        .find(|p| p.short_id == post_details.short_id)
+                        .iter()
+                        .position(|p| p.short_id == post_details.short_id)
                     {
-                        post.comments = post_details.comments;
-                        self.is_loading_comments.store(false, Ordering::Relaxed)
+                        self.posts[index].comments = post_details.comments;
+
+                        // Could also have been prefetched for another post
+                        if self.posts_list_state.selected() == Some(index) {
+                            self.details_attempts_left = None;
+                            self.is_loading_comments.store(false, Ordering::Relaxed)
+                        }
                     }
+                    self.resume_search(search);
                 }
-                Action::Key(ev) => self.handle_key_event(ev, tx_load, tx_load_details, tx_db)?,
-                Action::Mouse(ev) => {
-                    self.handle_mouse_event(ev, tx_load, tx_load_details, tx_db)?
+                Action::FetchFailed(instance, fetch, error)
+                    if self.is_current_instance(&instance) =>
+                {
+                    if let Fetch::Posts(mode) = &fetch {
+                        self.prefetching_pages.remove(&mode.path());
+                    }
+                    if self.is_current_fetch(&fetch) {
+                        self.fetch_failed(fetch, &error);
+                    }
                 }
+                Action::Retrying(instance, fetch, attempts_left)
+                    if self.is_current_instance(&instance) && self.is_current_fetch(&fetch) =>
+                {
+                    match fetch {
+                        Fetch::Posts(_) => self.posts_attempts_left = Some(attempts_left),
+                        Fetch::PostDetails => self.details_attempts_left = Some(attempts_left),
+                    }
+                }
+                // Loaded for an instance or mode which is no longer being browsed
+                Action::LoadPosts(..)
+                | Action::LoadComments(..)
+                | Action::LoadPostDetails(..)
+                | Action::Retrying(..)
+                | Action::FetchFailed(..) => {
+                    continue;
+                }
+                Action::Key(ev) => self.handle_key_event(ev, tx)?,
+                Action::Mouse(ev) => self.handle_mouse_event(ev, tx)?,
                 // Reset selection to first element to scroll whole
                 // table into view if scaling up
                 Action::Resize(_, h) => {
@@ -175,6 +236,24 @@ impl App {
 
         Ok(())
     }
+
+    /// Stop loading what failed to be fetched, showing the error. Anything
+    /// listed before switching to a mode or page which failed to load is
+    /// listed again.
+    fn fetch_failed(&mut self, fetch: Fetch, error: &color_eyre::Report) {
+        match fetch {
+            Fetch::Posts(_) => {
+                self.is_loading.store(false, Ordering::Relaxed);
+                self.posts_attempts_left = None;
+                self.return_to_fallback_mode();
+            }
+            Fetch::PostDetails => {
+                self.is_loading_comments.store(false, Ordering::Relaxed);
+                self.details_attempts_left = None;
+            }
+        }
+        self.fetch_error = Some(format!("{error:#}"));
+    }
 }
 
 fn on_thread_events(is_running: &Arc<AtomicBool>, tx_actions: Sender<Action>) -> Result<()> {
@@ -195,55 +274,238 @@ fn on_thread_events(is_running: &Arc<AtomicBool>, tx_actions: Sender<Action>) ->
     Ok(())
 }
 
-fn on_thread_posts(
-    is_running: &Arc<AtomicBool>,
+/// Everything needed by the threads which fetch results.
+#[derive(Clone)]
+struct Fetcher {
     db: Arc<DbPool>,
     client: Arc<Client>,
-    rx_load: Receiver<Mode>,
+    retry: RetryConfig,
+    /// Cleared on quitting, to stop waiting to retry a request
+    is_running: Arc<AtomicBool>,
+    /// Load results only from the offline cache
+    offline: bool,
+    /// Number of prefetch requests waiting to be fetched or being fetched
+    prefetching: Arc<AtomicUsize>,
+    /// Keep hidden posts in the results
+    show_hidden: Arc<AtomicBool>,
+}
+
+fn on_thread_posts(
+    is_running: &Arc<AtomicBool>,
+    fetcher: Fetcher,
+    rx_load: Receiver<PostsRequest>,
     tx_actions: Sender<Action>,
-    mut downloaded: DownloadedBytes,
 ) -> Result<()> {
+    let mut queue = VecDeque::new();
     while is_running.load(Ordering::Relaxed) {
-        match rx_load.recv_timeout(Duration::from_millis(50)) {
+        match next_request(&mut queue, &rx_load, &fetcher.prefetching) {
             Err(e) => match e {
                 // Check `is_running` again
                 RecvTimeoutError::Timeout => continue,
                 RecvTimeoutError::Disconnected => return Ok(()),
             },
-            Ok(mode) => {
-                let mut posts = fetch_posts(&client, &mode, &mut downloaded)?;
-                let conn = get_db_connection(&db)?;
-                update_posts(&conn, &mut posts)?;
-                tx_actions.send(Action::LoadPosts(posts))?;
+            Ok((instance, mode, purpose)) => {
+                let fetch = Fetch::Posts(mode.clone());
+                let on_retry = retry_notifier(&tx_actions, &instance, fetch.clone(), purpose);
+
+                // Failing to read or update the database fails the request,
+                // rather than ending the thread
+                match load_posts_request(&fetcher, &instance, &mode, purpose, on_retry) {
+                    Ok(action) => tx_actions.send(action)?,
+                    // Prefetched pages could be waited for once browsed
+                    Err(e) if purpose != Purpose::Refresh => {
+                        tx_actions.send(Action::FetchFailed(instance, fetch, e))?
+                    }
+                    // Refreshed results are already being browsed
+                    Err(_) => {}
+                }
+                finish_request(purpose, &fetcher.prefetching);
             }
         }
     }
     Ok(())
 }
 
+/// Load the posts (or comments) for the mode, returning the action to show
+/// them.
+fn load_posts_request(
+    fetcher: &Fetcher,
+    instance: &Arc<Instance>,
+    mode: &Mode,
+    purpose: Purpose,
+    on_retry: impl Fn(u32),
+) -> Result<Action> {
+    let conn = get_db_connection(&fetcher.db)?;
+    let show_hidden = fetcher.show_hidden.load(Ordering::Relaxed);
+
+    if mode.is_local() {
+        let mut posts = match mode {
+            Mode::Saved { sort, .. } => {
+                get_saved_posts(&conn, &instance.base_url, *sort, mode.get_page())?
+            }
+            _ => get_read_posts(&conn, &instance.base_url, mode.get_page())?,
+        };
+        let hidden = update_posts(&conn, &instance.base_url, &mut posts, show_hidden)?;
+        return Ok(Action::LoadPosts(
+            Arc::clone(instance),
+            mode.clone(),
+            posts,
+            hidden,
+        ));
+    }
+
+    if mode.lists_comments() {
+        let mut comments = load_listing(&conn, instance, mode, fetcher.offline, |v| {
+            fetch_comments(
+                &fetcher.client,
+                instance,
+                mode,
+                v,
+                purpose,
+                &fetcher.retry,
+                &fetcher.is_running,
+                on_retry,
+            )
+        })?;
+        index_comments(&conn, &instance.base_url, &comments)?;
+        update_comments(&conn, &instance.base_url, &mut comments)?;
+        return Ok(Action::LoadComments(
+            Arc::clone(instance),
+            mode.clone(),
+            comments,
+        ));
+    }
+
+    let mut posts = load_listing(&conn, instance, mode, fetcher.offline, |v| {
+        fetch_posts(
+            &fetcher.client,
+            instance,
+            mode,
+            v,
+            purpose,
+            &fetcher.retry,
+            &fetcher.is_running,
+            on_retry,
+        )
+    })?;
+    index_posts(&conn, &instance.base_url, &posts)?;
+    let hidden = update_posts(&conn, &instance.base_url, &mut posts, show_hidden)?;
+    Ok(Action::LoadPosts(
+        Arc::clone(instance),
+        mode.clone(),
+        posts,
+        hidden,
+    ))
+}
+
 fn on_thread_post_details(
     is_running: &Arc<AtomicBool>,
-    client: Arc<Client>,
-    rx_load_details: Receiver<String>,
+    fetcher: Fetcher,
+    rx_load_details: Receiver<DetailsRequest>,
     tx_actions: Sender<Action>,
-    mut downloaded: DownloadedBytes,
 ) -> Result<()> {
+    let mut queue = VecDeque::new();
     while is_running.load(Ordering::Relaxed) {
-        match rx_load_details.recv_timeout(Duration::from_millis(50)) {
+        match next_request(&mut queue, &rx_load_details, &fetcher.prefetching) {
             Err(e) => match e {
                 // Check `is_running` again
                 RecvTimeoutError::Timeout => continue,
                 RecvTimeoutError::Disconnected => return Ok(()),
             },
-            Ok(url) => {
-                let details = fetch_post_details(&client, &url, &mut downloaded)?;
-                tx_actions.send(Action::LoadPostDetails(details))?;
+            Ok((instance, short_id, purpose)) => {
+                let on_retry = retry_notifier(&tx_actions, &instance, Fetch::PostDetails, purpose);
+                let details = get_db_connection(&fetcher.db).and_then(|conn| {
+                    let details =
+                        load_post_details(&conn, &instance, &short_id, fetcher.offline, |v| {
+                            fetch_post_details(
+                                &fetcher.client,
+                                &instance,
+                                &short_id,
+                                v,
+                                purpose,
+                                &fetcher.retry,
+                                &fetcher.is_running,
+                                on_retry,
+                            )
+                        })?;
+                    index_comments(&conn, &instance.base_url, &details.comments)?;
+                    Ok(details)
+                });
+
+                match details {
+                    Ok(details) => tx_actions.send(Action::LoadPostDetails(instance, details))?,
+                    Err(e) if purpose == Purpose::Load => {
+                        tx_actions.send(Action::FetchFailed(instance, Fetch::PostDetails, e))?
+                    }
+                    // Nothing being browsed depends on prefetched results
+                    Err(_) => {}
+                }
+                finish_request(purpose, &fetcher.prefetching);
             }
         }
     }
     Ok(())
 }
 
+/// Callback for failed requests which are about to be retried, letting the main
+/// thread know how many attempts are left (unless prefetching or refreshing).
+fn retry_notifier(
+    tx_actions: &Sender<Action>,
+    instance: &Arc<Instance>,
+    fetch: Fetch,
+    purpose: Purpose,
+) -> impl Fn(u32) + use<> {
+    let tx_actions = tx_actions.clone();
+    let instance = Arc::clone(instance);
+    move |attempts_left| {
+        if purpose != Purpose::Load {
+            return;
+        }
+
+        // Closed channel means the program is exiting - nothing left to notify
+        let _ = tx_actions.send(Action::Retrying(
+            Arc::clone(&instance),
+            fetch.clone(),
+            attempts_left,
+        ));
+    }
+}
+
+/// Get the next request to handle, waiting for one if none are queued.
+///
+/// Only the latest request to load something being browsed can still match what
+/// is being browsed, so any requests queued before it are dropped - including
+/// prefetches, which would only delay it. Dropped prefetches are no longer
+/// counted as being prefetched, while the returned one is until it is finished.
+fn next_request<T>(
+    queue: &mut VecDeque<Request<T>>,
+    rx: &Receiver<Request<T>>,
+    prefetching: &AtomicUsize,
+) -> Result<Request<T>, RecvTimeoutError> {
+    if queue.is_empty() {
+        queue.push_back(rx.recv_timeout(Duration::from_millis(50))?);
+    }
+
+    for request in rx.try_iter() {
+        if request.2 == Purpose::Load {
+            let prefetches = queue.iter().filter(|r| r.2 == Purpose::Prefetch).count();
+            prefetching.fetch_sub(prefetches, Ordering::Relaxed);
+            queue.clear();
+        }
+        queue.push_back(request);
+    }
+
+    Ok(queue.pop_front().expect("queue should not be empty"))
+}
+
+/// Stop counting a finished request as being prefetched, so another prefetch
+/// can be requested.
+fn finish_request(purpose: Purpose, prefetching: &AtomicUsize) {
+    if purpose == Purpose::Prefetch {
+        prefetching.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 fn on_thread_db(
     is_running: &Arc<AtomicBool>,
     db: Arc<DbPool>,
@@ -261,14 +523,124 @@ fn on_thread_db(
 
         let conn = get_db_connection(&db)?;
         match action {
-            DatabaseAction::MarkPostRead(id) => {
-                mark_post_read(&conn, id)?;
+            DatabaseAction::MarkPostRead { instance, post } => {
+                mark_post_read(&conn, &instance, &post)?;
+            }
+            DatabaseAction::MarkPostUnread { instance, id } => {
+                mark_post_unread(&conn, &instance, id)?;
+            }
+            DatabaseAction::SavePost { instance, post } => {
+                save_post(&conn, &instance, &post)?;
+            }
+            DatabaseAction::UnsavePost { instance, id } => {
+                unsave_post(&conn, &instance, id)?;
             }
-            DatabaseAction::MarkPostUnread(id) => {
-                mark_post_unread(&conn, id)?;
+            DatabaseAction::HidePost { instance, id } => {
+                hide_post(&conn, &instance, id)?;
+            }
+            DatabaseAction::UnhidePost { instance, id } => {
+                unhide_post(&conn, &instance, id)?;
             }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::app::command::{Command, test::app};
+
+    #[test]
+    fn failed_fetch_keeps_listing() {
+        let (mut app, tx, _rx) = app();
+        let error = color_eyre::eyre::eyre!("failed requesting posts");
+
+        app.dispatch(Command::Select(1), &tx).unwrap();
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert_eq!(app.list_len(), 0);
+        app.fetch_failed(Fetch::Posts(app.mode.clone()), &error);
+        assert_eq!(app.mode, Mode::Hottest(1));
+        assert_eq!(app.list_len(), 3);
+        assert_eq!(app.posts_list_state.selected(), Some(1));
+        assert!(app.fetch_error.is_some());
+
+        // Switching again while loading still goes back to what was listed
+        app.dispatch(Command::SetMode(Mode::Newest(1)), &tx)
+            .unwrap();
+        app.dispatch(Command::SetMode(Mode::Active(1)), &tx)
+            .unwrap();
+        app.fetch_failed(Fetch::Posts(app.mode.clone()), &error);
+        assert_eq!(app.mode, Mode::Hottest(1));
+        assert_eq!(app.list_len(), 3);
+    }
+
+    #[test]
+    fn prefetched_page_not_fetched_again() {
+        let (mut app, tx, rx) = app();
+
+        app.plan_prefetch();
+        app.request_prefetches(&tx.posts, &tx.details).unwrap();
+        let (_, mode, purpose) = rx.posts.try_recv().unwrap();
+        assert_eq!((mode, purpose), (Mode::Hottest(2), Purpose::Prefetch));
+
+        // Waits for the prefetch instead
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        assert!(rx.posts.try_recv().is_err());
+        assert!(app.is_loading.load(Ordering::Relaxed));
+
+        // Fetched again once the prefetch is no longer pending
+        app.dispatch(Command::PreviousPage, &tx).unwrap();
+        app.prefetching_pages.clear();
+        app.dispatch(Command::NextPage, &tx).unwrap();
+        let (_, mode, purpose) = rx.posts.try_recv().unwrap();
+        assert_eq!((mode, purpose), (Mode::Hottest(2), Purpose::Load));
+    }
+
+    #[test]
+    fn queued_requests_coalesced() {
+        let instance = Arc::new(Instance::new(
+            String::from("lobste.rs"),
+            String::from("https://lobste.rs"),
+        ));
+        let (tx, rx) = channel();
+        let prefetching = AtomicUsize::new(0);
+        let send = |page: u8, purpose| {
+            if purpose == Purpose::Prefetch {
+                prefetching.fetch_add(1, Ordering::Relaxed);
+            }
+            tx.send((Arc::clone(&instance), page, purpose)).unwrap();
+        };
+        let mut queue = VecDeque::new();
+        let mut next = || {
+            let (_, page, purpose) = next_request(&mut queue, &rx, &prefetching).unwrap();
+            finish_request(purpose, &prefetching);
+            page
+        };
+
+        // Prefetches are handled in order, after loading what is being browsed
+        send(1, Purpose::Load);
+        send(2, Purpose::Prefetch);
+        send(3, Purpose::Prefetch);
+        assert_eq!([next(), next(), next()], [1, 2, 3]);
+        assert_eq!(prefetching.load(Ordering::Relaxed), 0);
+
+        // Anything queued before the latest load is outdated
+        send(4, Purpose::Prefetch);
+        send(5, Purpose::Load);
+        send(6, Purpose::Load);
+        send(7, Purpose::Prefetch);
+        assert_eq!([next(), next()], [6, 7]);
+        assert_eq!(prefetching.load(Ordering::Relaxed), 0);
+
+        // Still prefetching until finished
+        send(8, Purpose::Prefetch);
+        let (_, _, purpose) = next_request(&mut queue, &rx, &prefetching).unwrap();
+        assert_eq!(prefetching.load(Ordering::Relaxed), 1);
+        finish_request(purpose, &prefetching);
+        assert_eq!(prefetching.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,37 @@
+//! Local HTTP server for testing requests, without depending on a real
+//! instance.
+use std::{io::{BufRead, BufReader, Write}, net::TcpListener, sync::{Arc, Mutex}, thread};
+
+/// Serve the given responses in order, one per connection, returning the base
+/// URL of the server and the head of each request it has received.
+///
+/// Responses are given as the status, optionally followed by `|` and any
+/// headers (each ending in `\r\n`), e.g. `"429 Too Many Requests|Retry-After:
+/// 0\r\n"`. The body is always an empty JSON array.
+pub(super) fn stub_server(responses: &[&'static str]) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let responses = responses.to_vec();
+    let requests_clone = Arc::clone(&requests);
+    thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Read the request head before responding
+            let mut reader = BufReader::new(&stream);
+            let mut head = String::new();
+            while reader.read_line(&mut head).unwrap() > 2 {}
+
+            requests_clone.lock().unwrap().push(head);
+            let (status, headers) = response.split_once('|').unwrap_or((response, ""));
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\n{headers}Content-Length: 2\r\nConnection: close\r\n\r\n[]"
+            )
+            .unwrap();
+        }
+    });
+
+    (url, requests)
+}
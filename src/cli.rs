@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use clap::Parser;
 
-use crate::{config::DEFAULT_CONFIG_PATH, database::DEFAULT_DB_PATH};
+use crate::{config::DEFAULT_CONFIG_PATH, database::DEFAULT_DB_PATH, modes::parse_base_url};
 
 #[derive(Parser, Debug)]
 #[command(version, about="TUI for the fantastic lobste.rs", long_about = None)]
@@ -32,4 +32,20 @@ pub struct Args {
         env = "OMARO_DB"
     )]
     pub database: PathBuf,
+
+    #[arg(
+        short,
+        long,
+        value_parser = |s: &str| parse_base_url(s).map_err(|e| format!("{e:#}")),
+        help = "Base URL of an instance of the lobste.rs codebase to browse - overrides the config",
+        env = "OMARO_URL"
+    )]
+    pub url: Option<String>,
+
+    #[arg(
+        long,
+        help = "Browse only posts and comments loaded in previous sessions, without fetching anything",
+        env = "OMARO_OFFLINE"
+    )]
+    pub offline: bool,
 }
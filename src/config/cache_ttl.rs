@@ -0,0 +1,109 @@
+use chrono::TimeDelta;
+use serde::Deserialize;
+
+use crate::modes::Mode;
+
+/// Time (in seconds) after which the results for each mode expire. Expired
+/// results are refreshed in the background. `0` means they never expire.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct CacheTtlConfig {
+    #[serde(default = "default_hottest")]
+    pub hottest: u32,
+    #[serde(default = "default_newest")]
+    pub newest: u32,
+    #[serde(default = "default_active")]
+    pub active: u32,
+    #[serde(default = "default_top")]
+    pub top: u32,
+    #[serde(default = "default_comments")]
+    pub comments: u32,
+    #[serde(default = "default_tag")]
+    pub tag: u32,
+    #[serde(default = "default_domain")]
+    pub domain: u32,
+    /// Stories and threads of a user
+    #[serde(default = "default_user")]
+    pub user: u32,
+}
+
+impl CacheTtlConfig {
+    /// Time after which results for the given mode expire, if they do.
+    pub fn ttl(&self, mode: &Mode) -> Option<TimeDelta> {
+        let secs = match mode {
+            Mode::Hottest(_) => self.hottest,
+            Mode::Newest(_) => self.newest,
+            Mode::Active(_) => self.active,
+            Mode::Top { .. } => self.top,
+            Mode::Comments(_) => self.comments,
+            Mode::Tag(..) => self.tag,
+            Mode::Domain(..) => self.domain,
+            Mode::UserStories(..) | Mode::UserThreads(..) => self.user,
+            // Stored locally, so never out of date when loaded
+            Mode::History(_) | Mode::Saved { .. } => return None,
+        };
+
+        (secs > 0).then(|| TimeDelta::seconds(secs.into()))
+    }
+}
+
+impl Default for CacheTtlConfig {
+    fn default() -> Self {
+        Self {
+            hottest: default_hottest(),
+            newest: default_newest(),
+            active: default_active(),
+            top: default_top(),
+            comments: default_comments(),
+            tag: default_tag(),
+            domain: default_domain(),
+            user: default_user(),
+        }
+    }
+}
+
+fn default_hottest() -> u32 {
+    900
+}
+fn default_newest() -> u32 {
+    300
+}
+fn default_active() -> u32 {
+    600
+}
+fn default_top() -> u32 {
+    3600
+}
+fn default_comments() -> u32 {
+    300
+}
+fn default_tag() -> u32 {
+    900
+}
+fn default_domain() -> u32 {
+    1800
+}
+fn default_user() -> u32 {
+    1800
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ttl() {
+        let config = CacheTtlConfig {
+            newest: 0,
+            ..Default::default()
+        };
+
+        assert_eq!(config.ttl(&Mode::Hottest(1)), Some(TimeDelta::seconds(900)));
+        assert_eq!(
+            config.ttl(&Mode::UserThreads("user".to_string(), 2)),
+            Some(TimeDelta::seconds(1800))
+        );
+        // Never expire
+        assert_eq!(config.ttl(&Mode::Newest(1)), None);
+        assert_eq!(config.ttl(&Mode::History(1)), None);
+    }
+}
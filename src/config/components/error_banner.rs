@@ -0,0 +1,18 @@
+use ratatui::style::Color;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct ErrorBannerConfig {
+    #[serde(default = "default_fg")]
+    pub fg: Color,
+}
+
+impl Default for ErrorBannerConfig {
+    fn default() -> Self {
+        Self { fg: default_fg() }
+    }
+}
+
+fn default_fg() -> Color {
+    Color::Red
+}
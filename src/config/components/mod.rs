@@ -1,9 +1,11 @@
 mod borders;
 mod comment_count;
 mod downloaded;
+mod error_banner;
 mod header;
 mod keybind_hints;
 mod mode_info;
+mod saved;
 mod score_count;
 mod scrollbar;
 mod shortcuts;
@@ -13,9 +15,11 @@ mod submitted_user;
 pub use borders::*;
 pub use comment_count::*;
 pub use downloaded::*;
+pub use error_banner::*;
 pub use header::*;
 pub use keybind_hints::*;
 pub use mode_info::*;
+pub use saved::*;
 pub use score_count::*;
 pub use scrollbar::*;
 pub use shortcuts::*;
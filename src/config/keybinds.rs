@@ -0,0 +1,683 @@
+use std::{borrow::Cow, collections::HashMap, fmt::Display, str::FromStr};
+
+use color_eyre::eyre::{bail, eyre};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Deserializer};
+
+/// Keys which can be used to open the posts on a page, in order, unless they
+/// are bound to an action.
+const ALL_SHORTCUT_KEYS: &str = "123456789abcdefghijklmnopqrstuvwxyz;:$&*/|\\^`'\"[]()<>";
+
+/// Actions which can be bound to keys.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    NextRow,
+    PreviousRow,
+    LastRow,
+    FirstRow,
+    PreviousPage,
+    NextPage,
+    NextMode,
+    PreviousMode,
+    NextTopPeriod,
+    NextInstance,
+    OpenDomain,
+    UserStories,
+    UserThreads,
+    History,
+    Saved,
+    NextSavedSort,
+    OpenPost,
+    OpenComments,
+    MarkRead,
+    MarkUnread,
+    Save,
+    Unsave,
+    Hide,
+    UndoHide,
+    ToggleShowHidden,
+    ToggleTagFilterAction,
+    ToggleFilters,
+    PreviousTag,
+    NextTag,
+    MuteTag,
+    OnlyTag,
+    ToggleDetails,
+    ToggleStats,
+    ToggleKeybinds,
+    ClosePopup,
+    Refresh,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    SearchSeen,
+    CommandPalette,
+    Quit,
+    ForceQuit,
+}
+
+/// When an action's keys are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    Always,
+    /// Only while browsing the list of posts (or comments)
+    List,
+    /// Only while the details popup is open
+    Details,
+}
+
+impl KeyContext {
+    fn overlaps(self, other: Self) -> bool {
+        self == other || self == Self::Always || other == Self::Always
+    }
+}
+
+impl KeyAction {
+    /// Every action, in the order they are shown in the keybinds popup.
+    pub const ALL: [Self; 43] = [
+        Self::NextRow,
+        Self::PreviousRow,
+        Self::LastRow,
+        Self::FirstRow,
+        Self::PreviousPage,
+        Self::NextPage,
+        Self::NextMode,
+        Self::PreviousMode,
+        Self::NextTopPeriod,
+        Self::NextInstance,
+        Self::OpenDomain,
+        Self::UserStories,
+        Self::UserThreads,
+        Self::History,
+        Self::Saved,
+        Self::NextSavedSort,
+        Self::OpenPost,
+        Self::OpenComments,
+        Self::MarkRead,
+        Self::MarkUnread,
+        Self::Save,
+        Self::Unsave,
+        Self::Hide,
+        Self::UndoHide,
+        Self::ToggleShowHidden,
+        Self::ToggleTagFilterAction,
+        Self::ToggleFilters,
+        Self::PreviousTag,
+        Self::NextTag,
+        Self::MuteTag,
+        Self::OnlyTag,
+        Self::ToggleDetails,
+        Self::ToggleStats,
+        Self::ToggleKeybinds,
+        Self::ClosePopup,
+        Self::Refresh,
+        Self::Search,
+        Self::NextMatch,
+        Self::PreviousMatch,
+        Self::SearchSeen,
+        Self::CommandPalette,
+        Self::Quit,
+        Self::ForceQuit,
+    ];
+
+    /// Description shown in the keybinds popup.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::NextRow => "Scroll down",
+            Self::PreviousRow => "Scroll up",
+            Self::LastRow => "Scroll to last",
+            Self::FirstRow => "Scroll to first",
+            Self::PreviousPage => "Previous page",
+            Self::NextPage => "Next page",
+            Self::NextMode => "Next mode",
+            Self::PreviousMode => "Previous mode",
+            Self::NextTopPeriod => "Top posts period",
+            Self::NextInstance => "Next instance",
+            Self::OpenDomain => "Posts from domain",
+            Self::UserStories => "Stories by user",
+            Self::UserThreads => "Comments by user",
+            Self::History => "Read history",
+            Self::Saved => "Saved posts",
+            Self::NextSavedSort => "Order saved posts",
+            Self::OpenPost => "Open post / comment",
+            Self::OpenComments => "Open comments / post",
+            Self::MarkRead => "Mark read",
+            Self::MarkUnread => "Mark unread",
+            Self::Save => "Save for later",
+            Self::Unsave => "Unsave",
+            Self::Hide => "Hide post",
+            Self::UndoHide => "Undo hide",
+            Self::ToggleShowHidden => "Toggle hidden posts",
+            Self::ToggleTagFilterAction => "Hide / dim filtered posts",
+            Self::ToggleFilters => "Explain filters",
+            Self::PreviousTag => "Previous tag (details)",
+            Self::NextTag => "Next tag (details)",
+            Self::MuteTag => "Mute tag (details)",
+            Self::OnlyTag => "Only show tag (details)",
+            Self::ToggleDetails => "Toggle details",
+            Self::ToggleStats => "Toggle bandwidth stats",
+            Self::ToggleKeybinds => "Toggle keybinds",
+            Self::ClosePopup => "Close popup / clear search",
+            Self::Refresh => "Refresh",
+            Self::Search => "Search",
+            Self::NextMatch => "Next match",
+            Self::PreviousMatch => "Previous match",
+            Self::SearchSeen => "Search everything seen",
+            Self::CommandPalette => "Command palette",
+            Self::Quit => "Quit",
+            Self::ForceQuit => "Force quit",
+        }
+    }
+
+    /// Keys bound to the action unless configured otherwise.
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Self::NextRow => &["j", "Down"],
+            Self::PreviousRow => &["k", "Up"],
+            Self::LastRow => &["G", "End"],
+            Self::FirstRow => &["g", "Home"],
+            Self::PreviousPage => &["h", "Left", "PageUp"],
+            Self::NextPage => &["l", "Right", "PageDown"],
+            Self::NextMode => &["H", "Tab"],
+            Self::PreviousMode => &["L", "Shift+Tab"],
+            Self::NextTopPeriod => &["P"],
+            Self::NextInstance => &["I"],
+            Self::OpenDomain => &["D"],
+            Self::UserStories => &["S"],
+            Self::UserThreads => &["T"],
+            Self::History => &["Y"],
+            Self::Saved => &["V"],
+            Self::NextSavedSort => &["O"],
+            Self::OpenPost => &["Enter"],
+            Self::OpenComments => &["c"],
+            Self::MarkRead => &["r"],
+            Self::MarkUnread => &["u"],
+            Self::Save => &["A"],
+            Self::Unsave => &["X"],
+            Self::Hide => &["M"],
+            Self::UndoHide => &["U"],
+            Self::ToggleShowHidden => &["Z"],
+            Self::ToggleTagFilterAction => &["F"],
+            Self::ToggleFilters => &["E"],
+            Self::PreviousTag => &["h", "Left"],
+            Self::NextTag => &["l", "Right"],
+            Self::MuteTag => &["-"],
+            Self::OnlyTag => &["+"],
+            Self::ToggleDetails => &["K"],
+            Self::ToggleStats => &["B"],
+            Self::ToggleKeybinds => &["?"],
+            Self::ClosePopup => &["Esc"],
+            Self::Refresh => &["R", "F5"],
+            Self::Search => &["/"],
+            Self::NextMatch => &["n", "F3"],
+            Self::PreviousMatch => &["N", "Shift+F3"],
+            Self::SearchSeen => &["Ctrl+f"],
+            Self::CommandPalette => &[":", "Ctrl+p"],
+            Self::Quit => &["q"],
+            Self::ForceQuit => &["Ctrl+c"],
+        }
+    }
+
+    /// When the keys bound to the action are handled.
+    pub fn context(self) -> KeyContext {
+        match self {
+            Self::PreviousPage | Self::NextPage | Self::OpenDomain => KeyContext::List,
+            Self::PreviousTag | Self::NextTag | Self::MuteTag | Self::OnlyTag => {
+                KeyContext::Details
+            }
+            _ => KeyContext::Always,
+        }
+    }
+}
+
+impl Display for KeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Same as the names used in the config
+        let name = format!("{self:?}");
+        let mut snake = String::with_capacity(name.len() + 4);
+        for (i, c) in name.char_indices() {
+            if c.is_uppercase() && i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        write!(f, "{snake}")
+    }
+}
+
+/// A key, along with any modifiers held down with it, e.g. `Ctrl+c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// Chord for the key event. Shift is part of the character (or of
+    /// `BackTab`) rather than a modifier.
+    pub fn from_event(ev: &KeyEvent) -> Self {
+        let mut modifiers = ev.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if !matches!(ev.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers |= ev.modifiers & KeyModifiers::SHIFT;
+        }
+
+        Self {
+            code: ev.code,
+            modifiers,
+        }
+    }
+
+    /// Text shown for the chord in the keybinds popup.
+    pub fn label(&self) -> String {
+        match (self.code, self.modifiers.is_empty()) {
+            (KeyCode::Down, true) => "󰁅".to_owned(),
+            (KeyCode::Up, true) => "󰁝".to_owned(),
+            (KeyCode::Left, true) => "󰁍".to_owned(),
+            (KeyCode::Right, true) => "󰁔".to_owned(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = color_eyre::Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        // The key itself may be a `+`
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(modifiers) => (Some(modifiers), "+"),
+            None if s == "+" => (None, s),
+            None => match s.rsplit_once('+') {
+                Some((modifiers, key)) => (Some(modifiers), key),
+                None => (None, s),
+            },
+        };
+
+        let mut chord = Self {
+            code: parse_key_code(key).ok_or_else(|| eyre!("Not a valid key: '{s}'"))?,
+            modifiers: KeyModifiers::NONE,
+        };
+        for modifier in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            match modifier.trim().to_lowercase().as_str() {
+                "ctrl" | "c" => chord.modifiers |= KeyModifiers::CONTROL,
+                "alt" | "a" => chord.modifiers |= KeyModifiers::ALT,
+                "shift" | "s" => match chord.code {
+                    KeyCode::Char(c) => chord.code = KeyCode::Char(c.to_ascii_uppercase()),
+                    KeyCode::Tab => chord.code = KeyCode::BackTab,
+                    _ => chord.modifiers |= KeyModifiers::SHIFT,
+                },
+                _ => bail!("Not a valid modifier in '{s}'. Possible modifiers: Ctrl, Alt, Shift"),
+            }
+        }
+
+        Ok(chord)
+    }
+}
+
+fn parse_key_code(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+
+    let key = key.to_lowercase();
+    if let Some(n) = key.strip_prefix('f').and_then(|n| n.parse().ok()) {
+        return Some(KeyCode::F(n));
+    }
+
+    Some(match key.as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => return None,
+    })
+}
+
+impl Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            code => write!(f, "{code}"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyChord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = Cow::<'de, str>::deserialize(deserializer)?;
+        KeyChord::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Keys bound to each action, given as one key or a list of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyChords {
+    One(KeyChord),
+    Many(Vec<KeyChord>),
+}
+
+/// Keys bound to each action, and the keys left to open posts with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeybindsConfig {
+    bindings: Vec<(KeyAction, Vec<KeyChord>)>,
+    /// Keys bound to actions by default which were bound to other actions in
+    /// the config instead
+    overridden: Vec<(KeyChord, KeyAction)>,
+    shortcut_keys: Vec<char>,
+}
+
+impl KeybindsConfig {
+    /// Check the bindings for actions without any keys and keys bound to more
+    /// than one action at once, and work out the keys left to open posts with.
+    fn new(
+        bindings: Vec<(KeyAction, Vec<KeyChord>)>,
+        overridden: Vec<(KeyChord, KeyAction)>,
+    ) -> color_eyre::Result<Self> {
+        if let Some((action, _)) = bindings.iter().find(|(_, chords)| chords.is_empty()) {
+            let keys: Vec<String> = overridden
+                .iter()
+                .filter(|(_, a)| a == action)
+                .map(|(chord, _)| format!("'{chord}'"))
+                .collect();
+            if keys.is_empty() {
+                bail!("No keys are bound to '{action}'");
+            }
+            bail!(
+                "No keys are left bound to '{action}', as {} bound to other actions - bind other keys to '{action}'",
+                keys.join(", ")
+            );
+        }
+
+        for (i, (action, chords)) in bindings.iter().enumerate() {
+            for (other, other_chords) in &bindings[i + 1..] {
+                if let Some(chord) = chords.iter().find(|c| other_chords.contains(c))
+                    && action.context().overlaps(other.context())
+                {
+                    bail!("Key '{chord}' is bound to both '{action}' and '{other}'");
+                }
+            }
+        }
+
+        let bound: Vec<KeyCode> = bindings
+            .iter()
+            .flat_map(|(_, chords)| chords)
+            .filter(|c| c.modifiers.is_empty())
+            .map(|c| c.code)
+            .collect();
+        let shortcut_keys = ALL_SHORTCUT_KEYS
+            .chars()
+            .filter(|c| !bound.contains(&KeyCode::Char(*c)))
+            .collect();
+
+        Ok(Self {
+            bindings,
+            overridden,
+            shortcut_keys,
+        })
+    }
+
+    /// Every action along with the keys bound to it, in the order they are
+    /// shown in the keybinds popup.
+    pub fn bindings(&self) -> &[(KeyAction, Vec<KeyChord>)] {
+        &self.bindings
+    }
+
+    /// Keys bound to actions by default which the config binds to other
+    /// actions instead, along with the actions they were unbound from.
+    pub fn overridden(&self) -> &[(KeyChord, KeyAction)] {
+        &self.overridden
+    }
+
+    /// Keys bound to the action.
+    pub fn keys(&self, action: KeyAction) -> &[KeyChord] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map_or(&[], |(_, chords)| chords)
+    }
+
+    /// Action bound to the key, if any, given whether the details popup is
+    /// open.
+    pub fn action(&self, ev: &KeyEvent, in_details: bool) -> Option<KeyAction> {
+        let chord = KeyChord::from_event(ev);
+        let context = if in_details {
+            KeyContext::Details
+        } else {
+            KeyContext::List
+        };
+
+        self.bindings
+            .iter()
+            .find(|(action, chords)| action.context().overlaps(context) && chords.contains(&chord))
+            .map(|(action, _)| *action)
+    }
+
+    /// Keys which open the posts on a page, in order.
+    pub fn shortcut_keys(&self) -> &[char] {
+        &self.shortcut_keys
+    }
+}
+
+impl Default for KeybindsConfig {
+    fn default() -> Self {
+        let bindings = KeyAction::ALL
+            .iter()
+            .map(|action| {
+                let chords = action
+                    .default_keys()
+                    .iter()
+                    .map(|k| k.parse().expect("default keys should be valid"))
+                    .collect();
+                (*action, chords)
+            })
+            .collect();
+
+        Self::new(bindings, Vec::new()).expect("default keys should not conflict")
+    }
+}
+
+impl<'de> Deserialize<'de> for KeybindsConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut configured = HashMap::<KeyAction, KeyChords>::deserialize(deserializer)?;
+        let bindings: Vec<_> = Self::default()
+            .bindings
+            .into_iter()
+            .map(|(action, chords)| match configured.remove(&action) {
+                Some(KeyChords::One(chord)) => (action, vec![chord], true),
+                Some(KeyChords::Many(chords)) => (action, chords, true),
+                None => (action, chords, false),
+            })
+            .collect();
+
+        // Keys bound in the config are unbound from the actions they are bound
+        // to by default, so only keys bound twice in the config conflict
+        let configured: Vec<(KeyAction, KeyChord)> = bindings
+            .iter()
+            .filter(|(_, _, is_configured)| *is_configured)
+            .flat_map(|(action, chords, _)| chords.iter().map(|c| (*action, *c)))
+            .collect();
+        let mut overridden = Vec::new();
+        let bindings = bindings
+            .into_iter()
+            .map(|(action, mut chords, is_configured)| {
+                if !is_configured {
+                    chords.retain(|chord| {
+                        let is_overridden = configured
+                            .iter()
+                            .any(|(a, c)| c == chord && a.context().overlaps(action.context()));
+                        if is_overridden {
+                            overridden.push((*chord, action));
+                        }
+                        !is_overridden
+                    });
+                }
+                (action, chords)
+            })
+            .collect();
+
+        Self::new(bindings, overridden).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        KeyChord { code, modifiers }
+    }
+
+    #[test]
+    fn parse_key_chords() {
+        for (s, expected) in [
+            ("j", chord(KeyCode::Char('j'), KeyModifiers::NONE)),
+            ("G", chord(KeyCode::Char('G'), KeyModifiers::NONE)),
+            ("Shift+g", chord(KeyCode::Char('G'), KeyModifiers::NONE)),
+            ("+", chord(KeyCode::Char('+'), KeyModifiers::NONE)),
+            ("Ctrl++", chord(KeyCode::Char('+'), KeyModifiers::CONTROL)),
+            ("ctrl+c", chord(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            (
+                "Ctrl+Alt+Down",
+                chord(KeyCode::Down, KeyModifiers::CONTROL | KeyModifiers::ALT),
+            ),
+            ("Shift+Tab", chord(KeyCode::BackTab, KeyModifiers::NONE)),
+            ("pagedown", chord(KeyCode::PageDown, KeyModifiers::NONE)),
+            ("F5", chord(KeyCode::F(5), KeyModifiers::NONE)),
+            ("Space", chord(KeyCode::Char(' '), KeyModifiers::NONE)),
+        ] {
+            assert_eq!(s.parse::<KeyChord>().unwrap(), expected, "{s}");
+        }
+
+        for s in ["", "Ctrl+", "Hyper+j", "PageSideways"] {
+            assert!(s.parse::<KeyChord>().is_err(), "{s}");
+        }
+
+        // Shown the same way they are configured
+        for s in ["j", "Ctrl+c", "Shift+Tab", "PageUp", "F5", "Enter", "Space"] {
+            assert_eq!(s.parse::<KeyChord>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn configured_keybinds() {
+        let keybinds: KeybindsConfig = toml::from_str(
+            r#"
+            next_row = "n"
+            previous_row = ["p", "Ctrl+p"]
+            "#,
+        )
+        .unwrap();
+
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        let n = key(KeyCode::Char('n'), KeyModifiers::NONE);
+        let j = key(KeyCode::Char('j'), KeyModifiers::NONE);
+        let ctrl_p = key(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        let shift_g = key(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        let h = key(KeyCode::Char('h'), KeyModifiers::NONE);
+
+        assert_eq!(keybinds.action(&n, false), Some(KeyAction::NextRow));
+        // Unbound from the action it is bound to by default
+        assert_eq!(
+            keybinds.keys(KeyAction::NextMatch),
+            &[chord(KeyCode::F(3), KeyModifiers::NONE)]
+        );
+        assert_eq!(
+            keybinds.overridden(),
+            &[
+                (
+                    chord(KeyCode::Char('n'), KeyModifiers::NONE),
+                    KeyAction::NextMatch
+                ),
+                (
+                    chord(KeyCode::Char('p'), KeyModifiers::CONTROL),
+                    KeyAction::CommandPalette
+                ),
+            ]
+        );
+        assert_eq!(keybinds.action(&j, false), None);
+        assert_eq!(
+            keybinds.action(&ctrl_p, false),
+            Some(KeyAction::PreviousRow)
+        );
+        assert_eq!(keybinds.action(&shift_g, false), Some(KeyAction::LastRow));
+        assert_eq!(keybinds.action(&h, false), Some(KeyAction::PreviousPage));
+        assert_eq!(keybinds.action(&h, true), Some(KeyAction::PreviousTag));
+
+        // Bound keys are left out of the shortcut keys, and unbound ones used
+        let shortcut_keys: String = keybinds.shortcut_keys().iter().collect();
+        assert!(shortcut_keys.starts_with("123456789abdefijkmost"));
+    }
+
+    #[test]
+    fn conflicting_keybinds() {
+        for toml in [
+            r#"
+            quit = "x"
+            refresh = "x"
+            "#,
+            r#"
+            next_row = "x"
+            previous_page = ["y", "x"]
+            "#,
+        ] {
+            assert!(toml::from_str::<KeybindsConfig>(toml).is_err(), "{toml}");
+        }
+
+        // Keys bound by default are unbound from their actions instead
+        let keybinds: KeybindsConfig = toml::from_str(r#"quit = "j""#).unwrap();
+        let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(keybinds.action(&j, false), Some(KeyAction::Quit));
+        assert_eq!(keybinds.keys(KeyAction::NextRow).len(), 1);
+
+        // Every action needs a key
+        let error = |toml| {
+            toml::from_str::<KeybindsConfig>(toml)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(error(r#"quit = "Esc""#).contains("No keys are left bound to 'close_popup'"));
+        assert!(error("quit = []").contains("No keys are bound to 'quit'"));
+
+        // Keys for actions which are never handled at the same time
+        assert!(toml::from_str::<KeybindsConfig>(r#"next_tag = "D""#).is_ok());
+        // Unknown actions
+        assert!(toml::from_str::<KeybindsConfig>(r#"fly = "f""#).is_err());
+    }
+}
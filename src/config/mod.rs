@@ -2,12 +2,23 @@ use std::{fs::read_to_string, path::{Path, PathBuf}, sync::LazyLock};
 
 use color_eyre::{Result, Section, eyre::{Context, eyre}};
 use dirs::config_dir;
-use serde::Deserialize;
+use reqwest::Url;
+use serde::{Deserialize, Deserializer};
 
+mod cache_ttl;
 mod components;
+mod keybinds;
+mod prefetch;
+mod retry;
+mod tag_filter;
+pub use cache_ttl::CacheTtlConfig;
 pub use components::*;
+pub use keybinds::{KeyAction, KeybindsConfig};
+pub use prefetch::PrefetchConfig;
+pub use retry::RetryConfig;
+pub use tag_filter::{TagFilterAction, TagFilterConfig};
 
-use crate::modes::Mode;
+use crate::{filters::FilterRule, modes::{DEFAULT_BASE_URL, Mode, SavedSort, TopPeriod, deserialize_tags, parse_base_url}};
 
 pub static DEFAULT_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
     config_dir()
@@ -18,27 +29,169 @@ pub static DEFAULT_CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
 
 #[derive(Debug, Deserialize, PartialEq, Eq)]
 pub struct Config {
+    /// Base URL of the instance of the lobste.rs codebase to browse
+    #[serde(
+        default = "_default_base_url",
+        deserialize_with = "deserialize_base_url"
+    )]
+    pub base_url: String,
+    /// Instances which can be switched between while running
+    #[serde(default, deserialize_with = "deserialize_instances")]
+    pub instances: Vec<InstanceConfig>,
     #[serde(default)]
     pub default_mode: Mode,
     #[serde(default = "_default_true")]
     pub opening_comments_marks_posts_read: bool,
     #[serde(default = "_default_true")]
     pub previewing_comments_marks_posts_read: bool,
+    /// Tags browsed in the tag mode
+    #[serde(default, deserialize_with = "deserialize_tags")]
+    pub tags: Vec<String>,
+    /// Period used when switching to the top mode
+    #[serde(default)]
+    pub top_period: TopPeriod,
+    /// Order used when switching to the saved posts
+    #[serde(default)]
+    pub saved_sort: SavedSort,
+    /// Browse only results cached from previous sessions, without fetching
+    #[serde(default)]
+    pub offline: bool,
+    /// Retrying failed requests
+    #[serde(default)]
+    pub retry: RetryConfig,
+    /// Expiring results loaded for each mode
+    #[serde(default)]
+    pub cache_ttl: CacheTtlConfig,
+    /// Fetching results in the background before they are browsed
+    #[serde(default)]
+    pub prefetch: PrefetchConfig,
+    /// Filtering posts by their tags
+    #[serde(default)]
+    pub tag_filter: TagFilterConfig,
+    /// Rules hiding, dimming or highlighting posts, the first matching one
+    /// being applied
+    #[serde(default)]
+    pub filters: Vec<FilterRule>,
+    /// Keys bound to each action
+    #[serde(default)]
+    pub keybinds: KeybindsConfig,
 
     pub ui: UiConfig,
 }
 
+/// A named instance of the lobste.rs codebase.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct InstanceConfig {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_base_url")]
+    pub base_url: String,
+}
+
+impl InstanceConfig {
+    /// Create an instance from its base URL, using the host as its name.
+    pub fn from_base_url(base_url: &str) -> Self {
+        let name = Url::parse(base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(String::from))
+            .unwrap_or_else(|| base_url.to_owned());
+
+        Self {
+            name,
+            base_url: base_url.to_owned(),
+        }
+    }
+}
+
+impl Config {
+    /// Set the base URL of the instance to start browsing, adding it to the
+    /// configured instances if it isn't one of them already.
+    pub fn set_base_url(&mut self, base_url: String) {
+        if !self.instances.is_empty() && !self.instances.iter().any(|i| i.base_url == base_url) {
+            let mut instance = InstanceConfig::from_base_url(&base_url);
+            // Keep names unique, e.g. for the same host with another scheme
+            if self.instances.iter().any(|i| i.name == instance.name) {
+                instance.name = base_url.clone();
+            }
+            self.instances.insert(0, instance);
+        }
+
+        self.base_url = base_url;
+    }
+
+    /// Get the instances which can be browsed, and the index of the one to
+    /// start browsing.
+    pub fn get_instances(&self) -> (Vec<InstanceConfig>, usize) {
+        if self.instances.is_empty() {
+            return (vec![InstanceConfig::from_base_url(&self.base_url)], 0);
+        }
+
+        let index = self
+            .instances
+            .iter()
+            .position(|i| i.base_url == self.base_url)
+            .unwrap_or_default();
+
+        (self.instances.clone(), index)
+    }
+}
+
 fn _default_true() -> bool {
     true
 }
 
+fn _default_base_url() -> String {
+    String::from(DEFAULT_BASE_URL)
+}
+
+fn deserialize_base_url<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    parse_base_url(&s).map_err(serde::de::Error::custom)
+}
+
+/// Deserialize the configured instances, which must each have a different name.
+fn deserialize_instances<'de, D>(deserializer: D) -> Result<Vec<InstanceConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let instances = Vec::<InstanceConfig>::deserialize(deserializer)?;
+
+    for (i, instance) in instances.iter().enumerate() {
+        if instances[..i]
+            .iter()
+            .any(|other| other.name == instance.name)
+        {
+            return Err(serde::de::Error::custom(format!(
+                "Instance name '{}' is used more than once - each instance needs its own name",
+                instance.name
+            )));
+        }
+    }
+
+    Ok(instances)
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
+            base_url: _default_base_url(),
+            instances: Vec::new(),
             default_mode: Default::default(),
             ui: UiConfig::default(),
             opening_comments_marks_posts_read: true,
             previewing_comments_marks_posts_read: true,
+            tags: Vec::new(),
+            top_period: Default::default(),
+            saved_sort: Default::default(),
+            offline: false,
+            retry: Default::default(),
+            cache_ttl: Default::default(),
+            prefetch: Default::default(),
+            tag_filter: Default::default(),
+            filters: Vec::new(),
+            keybinds: Default::default(),
         }
     }
 }
@@ -51,12 +204,14 @@ pub struct UiConfig {
     pub comment_count: CommentCountConfig,
     pub submitted_elapsed: SubmittedElapsedConfig,
     pub submitted_user: SubmittedUserConfig,
+    pub saved: SavedConfig,
     pub scrollbar: ScrollbarConfig,
     pub score_count: ScoreCountConfig,
     pub shortcuts: ShortcutsUiConfig,
     pub downloaded: DownloadedConfig,
     pub keybind_hints: KeybindHintsConfig,
     pub mode_info: ModeInfoConfig,
+    pub error_banner: ErrorBannerConfig,
 }
 
 pub fn get_config(config: &Path, clean: bool) -> Result<Config> {
@@ -125,4 +280,59 @@ mod test {
         assert!(get_config(&configs_dir.join("minimaler.toml"), false).is_ok());
         assert!(get_config(&configs_dir.join("minimalest.toml"), false).is_ok());
     }
+
+    #[test]
+    fn instances() {
+        let mut config = Config::default();
+        let (instances, index) = config.get_instances();
+        assert_eq!(
+            instances,
+            vec![InstanceConfig::from_base_url(DEFAULT_BASE_URL)]
+        );
+        assert_eq!(instances[index].name, "lobste.rs");
+
+        config.instances = vec![
+            InstanceConfig::from_base_url("https://example.com"),
+            InstanceConfig::from_base_url(DEFAULT_BASE_URL),
+        ];
+        assert_eq!(config.get_instances().1, 1);
+
+        // Overriding the base URL adds it as an instance
+        config.set_base_url(String::from("http://localhost:3000"));
+        let (instances, index) = config.get_instances();
+        assert_eq!(instances.len(), 3);
+        assert_eq!(index, 0);
+        assert_eq!(instances[index].name, "localhost");
+
+        config.set_base_url(String::from("https://example.com"));
+        let (instances, index) = config.get_instances();
+        assert_eq!(instances.len(), 3);
+        assert_eq!(instances[index].base_url, "https://example.com");
+
+        // Added with its URL as its name if the host is taken
+        config.set_base_url(String::from("http://example.com"));
+        let (instances, index) = config.get_instances();
+        assert_eq!(instances.len(), 4);
+        assert_eq!(instances[index].name, "http://example.com");
+    }
+
+    #[test]
+    fn duplicate_instance_names() {
+        let toml = |second: &str| {
+            format!(
+                "[[instances]]\nname = \"lobsters\"\nbase_url = \"https://lobste.rs\"\n\
+                 [[instances]]\nname = \"{second}\"\nbase_url = \"http://localhost:3000\"\n\
+                 [ui]"
+            )
+        };
+
+        let config: Config = toml::from_str(&toml("local")).unwrap();
+        assert_eq!(config.instances.len(), 2);
+
+        let err = toml::from_str::<Config>(&toml("lobsters")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("'lobsters' is used more than once")
+        );
+    }
 }
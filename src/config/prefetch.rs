@@ -0,0 +1,42 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct PrefetchConfig {
+    /// Fetch the next page of posts in the background once the current page
+    /// has loaded
+    #[serde(default = "default_next_page")]
+    pub next_page: bool,
+    /// Number of posts at the top of the page to fetch comments for in the
+    /// background
+    #[serde(default)]
+    pub post_details: usize,
+    /// Maximum number of prefetch requests waiting to be fetched or being
+    /// fetched at once
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Bytes which can be prefetched from each instance in a session, after
+    /// which prefetching stops. `0` means no limit
+    #[serde(default = "default_byte_budget")]
+    pub byte_budget: u64,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self {
+            next_page: default_next_page(),
+            post_details: 0,
+            concurrency: default_concurrency(),
+            byte_budget: default_byte_budget(),
+        }
+    }
+}
+
+fn default_next_page() -> bool {
+    true
+}
+fn default_concurrency() -> usize {
+    2
+}
+fn default_byte_budget() -> u64 {
+    2_000_000
+}
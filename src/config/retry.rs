@@ -0,0 +1,35 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Maximum number of attempts for each request, including the first
+    #[serde(default = "default_attempts")]
+    pub attempts: u32,
+    /// Delay before the first retry, doubled for each retry after that
+    #[serde(default = "default_initial_delay_ms")]
+    pub initial_delay_ms: u64,
+    /// Upper limit for the delay between attempts, including delays requested
+    /// with a `Retry-After` header
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            attempts: default_attempts(),
+            initial_delay_ms: default_initial_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+        }
+    }
+}
+
+fn default_attempts() -> u32 {
+    4
+}
+fn default_initial_delay_ms() -> u64 {
+    500
+}
+fn default_max_delay_ms() -> u64 {
+    10_000
+}
@@ -0,0 +1,120 @@
+use std::{borrow::Cow, fmt::Display};
+
+use serde::{Deserialize, Deserializer};
+
+use crate::modes::deserialize_tags;
+
+/// What to do with posts filtered out by their tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagFilterAction {
+    /// Drop them from listings
+    #[default]
+    Hide,
+    /// List them, dimmed
+    Dim,
+}
+
+impl TagFilterAction {
+    /// Switch to the other action.
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Hide => Self::Dim,
+            Self::Dim => Self::Hide,
+        }
+    }
+}
+
+impl Display for TagFilterAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hide => write!(f, "hidden"),
+            Self::Dim => write!(f, "dimmed"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TagFilterAction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = Cow::<'de, str>::deserialize(deserializer)?;
+        match s.trim().to_lowercase().as_str() {
+            "hide" => Ok(Self::Hide),
+            "dim" => Ok(Self::Dim),
+            _ => Err(serde::de::Error::custom(format!(
+                "Not a valid action: '{s}'. Possible actions: hide, dim"
+            ))),
+        }
+    }
+}
+
+/// Filtering posts by their tags.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct TagFilterConfig {
+    /// Posts with any of these tags are filtered out
+    #[serde(default, deserialize_with = "deserialize_tags")]
+    pub muted: Vec<String>,
+    /// If not empty, posts without any of these tags are filtered out
+    #[serde(default, deserialize_with = "deserialize_tags")]
+    pub only: Vec<String>,
+    #[serde(default)]
+    pub action: TagFilterAction,
+}
+
+impl TagFilterConfig {
+    /// Whether a post with the given tags is filtered out.
+    pub fn filters(&self, tags: &[String]) -> bool {
+        tags.iter().any(|t| self.muted.contains(t))
+            || (!self.only.is_empty() && !tags.iter().any(|t| self.only.contains(t)))
+    }
+
+    /// Mute the tag, or unmute it if it is already muted.
+    pub fn toggle_muted(&mut self, tag: &str) {
+        toggle(&mut self.muted, tag);
+    }
+
+    /// Only show posts with the tag (along with any other such tags), or stop
+    /// doing so if already the case.
+    pub fn toggle_only(&mut self, tag: &str) {
+        toggle(&mut self.only, tag);
+    }
+}
+
+fn toggle(tags: &mut Vec<String>, tag: &str) {
+    match tags.iter().position(|t| t == tag) {
+        Some(index) => {
+            tags.remove(index);
+        }
+        None => tags.push(tag.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn filters_by_tags() {
+        let mut config = TagFilterConfig::default();
+        assert!(!config.filters(&tags(&["rust", "culture"])));
+
+        config.toggle_muted("culture");
+        assert!(config.filters(&tags(&["rust", "culture"])));
+        assert!(!config.filters(&tags(&["rust"])));
+
+        config.toggle_only("rust");
+        assert!(!config.filters(&tags(&["rust"])));
+        assert!(config.filters(&tags(&["databases"])));
+        // Muted tags take precedence
+        assert!(config.filters(&tags(&["rust", "culture"])));
+
+        config.toggle_muted("culture");
+        config.toggle_only("rust");
+        assert_eq!(config, TagFilterConfig::default());
+    }
+}
@@ -1,5 +1,8 @@
 use chrono::{DateTime, FixedOffset};
-use serde::{Deserialize, Deserializer};
+use reqwest::{Url, blocking::RequestBuilder, header::{ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED}};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::modes::parse_domain;
 
 fn deserialize_date_from_str<'de, D>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error>
 where
@@ -10,7 +13,7 @@ where
     Ok(date)
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Post {
     pub short_id: String,
     #[serde(deserialize_with = "deserialize_date_from_str")]
@@ -21,27 +24,49 @@ pub struct Post {
     pub comment_count: u32,
     pub submitter_user: String,
     pub tags: Vec<String>,
-    pub short_id_url: String,
     pub comments_url: String,
+    // short_id_url: String,
     // description_plain: String,
     // flags: u32,
     // user_is_author
 
-    // Custom properties
-    #[serde(default)]
+    // Custom properties - not stored with cached listings, as they are kept
+    // up to date separately
+    #[serde(default, skip_serializing)]
     pub is_read: bool,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing)]
+    pub is_saved: bool,
+
+    #[serde(default, skip_serializing)]
+    pub is_hidden: bool,
+
+    #[serde(default, skip_serializing)]
+    pub is_filtered: bool,
+
+    /// Index of the filter rule applied to the post, if any matches it
+    #[serde(default, skip_serializing)]
+    pub filter_rule: Option<usize>,
+
+    #[serde(default, skip_serializing)]
     pub comments: Vec<PostComment>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl Post {
+    /// Domain of the story URL, if the post links to one.
+    pub fn domain(&self) -> Option<String> {
+        let url = Url::parse(&self.url).ok()?;
+        parse_domain(url.host_str()?).ok()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PostDetails {
     pub short_id: String,
     pub comments: Vec<PostComment>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PostComment {
     #[serde(deserialize_with = "deserialize_date_from_str")]
     pub created_at: DateTime<FixedOffset>,
@@ -50,6 +75,10 @@ pub struct PostComment {
     pub depth: usize,
     pub commenting_user: String,
     pub url: String,
+    /// Title of the post this comment was made on, if the post has been seen -
+    /// not stored with cached listings, as it is looked up separately
+    #[serde(default, skip_serializing)]
+    pub post_title: Option<String>,
     // #[serde(deserialize_with = "deserialize_date_from_str")]
     // pub last_edited_at: DateTime<FixedOffset>,
     // pub flags: u32,
@@ -59,3 +88,209 @@ pub struct PostComment {
     // pub is_deleted: bool,
     // pub is_moderated: bool,
 }
+
+impl PostComment {
+    /// Short ID and slug of the post this comment was made on, from the
+    /// comment's URL relative to the base URL of the instance (e.g.
+    /// `https://lobste.rs/s/abc123/title#c_def456`).
+    fn story_segments(&self, base_url: &str) -> Option<(String, Option<String>)> {
+        let url = Url::parse(&self.url).ok()?;
+        let base = Url::parse(base_url).ok()?;
+
+        let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+        for base_segment in base.path_segments()?.filter(|s| !s.is_empty()) {
+            if segments.next()? != base_segment {
+                return None;
+            }
+        }
+
+        if segments.next()? != "s" {
+            return None;
+        }
+        let id = segments.next()?.to_owned();
+        Some((id, segments.next().map(String::from)))
+    }
+
+    /// URL of the post this comment was made on, derived from the comment's
+    /// URL.
+    pub fn story_url(&self, base_url: &str) -> Option<String> {
+        self.story_segments(base_url)?;
+
+        let mut url = Url::parse(&self.url).ok()?;
+        url.set_fragment(None);
+        Some(url.to_string())
+    }
+
+    /// Short ID of the post this comment was made on, from the comment's URL.
+    pub fn story_id(&self, base_url: &str) -> Option<String> {
+        self.story_segments(base_url).map(|(id, _)| id)
+    }
+
+    /// Title of the post this comment was made on, if the post has been seen,
+    /// otherwise approximated from the slug in the comment's URL.
+    pub fn story_title(&self, base_url: &str) -> Option<String> {
+        if let Some(title) = &self.post_title {
+            return Some(title.clone());
+        }
+
+        let (_, slug) = self.story_segments(base_url)?;
+        slug.map(|s| s.replace('_', " "))
+            .filter(|t| !t.trim().is_empty())
+    }
+}
+
+/// Validators from the `ETag` and `Last-Modified` headers of a response, used
+/// to only fetch data again if it has changed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(String::from)
+        };
+
+        Self {
+            etag: get(ETAG),
+            last_modified: get(LAST_MODIFIED),
+        }
+    }
+
+    /// Make the request conditional on the data having changed since these
+    /// validators were received.
+    pub fn apply(&self, mut req: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            req = req.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            req = req.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        req
+    }
+}
+
+/// Bytes downloaded, split by what was downloaded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bandwidth {
+    /// Listings of posts, or of comments for modes which list comments
+    pub listings: u64,
+    /// Post details, including their comments
+    pub comments: u64,
+    /// How much of the listings and comments was prefetched
+    pub prefetched: u64,
+}
+
+impl Bandwidth {
+    pub fn total(&self) -> u64 {
+        self.listings + self.comments
+    }
+}
+
+/// Bytes downloaded from an instance across sessions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BandwidthTotals {
+    pub today: Bandwidth,
+    pub all_time: Bandwidth,
+}
+
+/// Post with the given short ID, as fetched from lobste.rs, for tests.
+#[cfg(test)]
+pub fn test_post(id: &str) -> Post {
+    serde_json::from_value(serde_json::json!({
+        "short_id": id,
+        "created_at": "2025-01-01T12:00:00.000-06:00",
+        "title": format!("Post {id}"),
+        "url": format!("https://example.com/{id}"),
+        "score": 1,
+        "comment_count": 0,
+        "submitter_user": "user",
+        "tags": ["rust"],
+        "comments_url": format!("https://lobste.rs/s/{id}"),
+    }))
+    .unwrap()
+}
+
+/// Comment with the given URL and text, as fetched from lobste.rs, for tests.
+#[cfg(test)]
+pub fn test_comment(url: &str, text: &str) -> PostComment {
+    serde_json::from_value(serde_json::json!({
+        "created_at": "2025-01-01T12:00:00.000-06:00",
+        "score": 1,
+        "comment_plain": text,
+        "depth": 0,
+        "commenting_user": "commenter",
+        "url": url,
+    }))
+    .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn comment(url: &str) -> PostComment {
+        test_comment(url, "")
+    }
+
+    #[test]
+    fn comment_story() {
+        let base_url = "https://lobste.rs";
+        let mut c = comment("https://lobste.rs/s/abc123/what_s_new_in_rust#c_def456");
+        assert_eq!(
+            c.story_url(base_url).as_deref(),
+            Some("https://lobste.rs/s/abc123/what_s_new_in_rust")
+        );
+        assert_eq!(c.story_id(base_url).as_deref(), Some("abc123"));
+        assert_eq!(
+            c.story_title(base_url).as_deref(),
+            Some("what s new in rust")
+        );
+        c.post_title = Some(String::from("What's new in Rust 1.90?"));
+        assert_eq!(
+            c.story_title(base_url).as_deref(),
+            Some("What's new in Rust 1.90?")
+        );
+
+        let c = comment("https://lobste.rs/c/def456");
+        assert_eq!(c.story_url(base_url), None);
+        assert_eq!(c.story_title(base_url), None);
+
+        // Instance served under a base path
+        let base_url = "https://example.com/lobsters";
+        let c = comment("https://example.com/lobsters/s/abc123/title#c_def456");
+        assert_eq!(c.story_id(base_url).as_deref(), Some("abc123"));
+        assert_eq!(c.story_title(base_url).as_deref(), Some("title"));
+        assert_eq!(c.story_id("https://example.com/other"), None);
+    }
+
+    #[test]
+    fn post_serialisation_round_trip() {
+        let json = r#"{
+            "short_id": "abc123",
+            "created_at": "2025-01-01T12:00:00.000-06:00",
+            "title": "What's new in Rust",
+            "url": "https://example.com/rust",
+            "score": 10,
+            "comment_count": 2,
+            "submitter_user": "user",
+            "tags": ["rust"],
+            "comments_url": "https://lobste.rs/s/abc123/what_s_new_in_rust"
+        }"#;
+        let mut post: Post = serde_json::from_str(json).unwrap();
+        post.is_read = true;
+
+        let cached: Post = serde_json::from_str(&serde_json::to_string(&post).unwrap()).unwrap();
+        assert_eq!(cached.created_at, post.created_at);
+        assert_eq!(cached.title, post.title);
+        // Read status is kept up to date separately
+        assert!(!cached.is_read);
+    }
+}
@@ -0,0 +1,10 @@
+CREATE TABLE posts_old
+(
+    id TEXT NOT NULL PRIMARY KEY
+) STRICT ;
+
+INSERT INTO posts_old (id)
+SELECT id FROM posts WHERE instance = 'https://lobste.rs';
+
+DROP TABLE posts;
+ALTER TABLE posts_old RENAME TO posts;
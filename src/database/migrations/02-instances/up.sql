@@ -0,0 +1,13 @@
+-- Namespace read posts by the base URL of the instance they were read on
+CREATE TABLE posts_new
+(
+    instance TEXT NOT NULL,
+    id TEXT NOT NULL,
+    PRIMARY KEY (instance, id)
+) STRICT ;
+
+INSERT INTO posts_new (instance, id)
+SELECT 'https://lobste.rs', id FROM posts;
+
+DROP TABLE posts;
+ALTER TABLE posts_new RENAME TO posts;
@@ -0,0 +1,19 @@
+-- Last fetched results for each listing (mode and page), stored as JSON
+CREATE TABLE IF NOT EXISTS listings
+(
+    instance TEXT NOT NULL,
+    path TEXT NOT NULL,
+    data TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    PRIMARY KEY (instance, path)
+) STRICT ;
+
+-- Last fetched details (including comments) for each post, stored as JSON
+CREATE TABLE IF NOT EXISTS post_details
+(
+    instance TEXT NOT NULL,
+    id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    PRIMARY KEY (instance, id)
+) STRICT ;
@@ -0,0 +1,5 @@
+ALTER TABLE listings DROP COLUMN etag;
+ALTER TABLE listings DROP COLUMN last_modified;
+
+ALTER TABLE post_details DROP COLUMN etag;
+ALTER TABLE post_details DROP COLUMN last_modified;
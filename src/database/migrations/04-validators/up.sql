@@ -0,0 +1,6 @@
+-- Validators for conditional requests, from the `ETag` and `Last-Modified` headers
+ALTER TABLE listings ADD COLUMN etag TEXT;
+ALTER TABLE listings ADD COLUMN last_modified TEXT;
+
+ALTER TABLE post_details ADD COLUMN etag TEXT;
+ALTER TABLE post_details ADD COLUMN last_modified TEXT;
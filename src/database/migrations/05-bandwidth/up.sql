@@ -0,0 +1,10 @@
+-- Bytes downloaded from each instance, per day (in local time)
+CREATE TABLE IF NOT EXISTS bandwidth
+(
+    instance TEXT NOT NULL,
+    day TEXT NOT NULL,
+    listings INTEGER NOT NULL DEFAULT 0,
+    comments INTEGER NOT NULL DEFAULT 0,
+    prefetched INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (instance, day)
+) STRICT ;
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS posts_read_at;
+
+ALTER TABLE posts DROP COLUMN read_at;
+ALTER TABLE posts DROP COLUMN title;
+ALTER TABLE posts DROP COLUMN url;
+ALTER TABLE posts DROP COLUMN tags;
+ALTER TABLE posts DROP COLUMN submitter;
+ALTER TABLE posts DROP COLUMN data;
@@ -0,0 +1,11 @@
+-- When each post was first read, and enough about it to list it again. Posts
+-- read before this was recorded only have their ID
+ALTER TABLE posts ADD COLUMN read_at INTEGER;
+ALTER TABLE posts ADD COLUMN title TEXT;
+ALTER TABLE posts ADD COLUMN url TEXT;
+ALTER TABLE posts ADD COLUMN tags TEXT;
+ALTER TABLE posts ADD COLUMN submitter TEXT;
+-- The whole post, stored as JSON
+ALTER TABLE posts ADD COLUMN data TEXT;
+
+CREATE INDEX IF NOT EXISTS posts_read_at ON posts (instance, read_at);
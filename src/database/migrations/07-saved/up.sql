@@ -0,0 +1,9 @@
+-- Posts saved to read later, stored as JSON
+CREATE TABLE IF NOT EXISTS saved
+(
+    instance TEXT NOT NULL,
+    id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    saved_at INTEGER NOT NULL,
+    PRIMARY KEY (instance, id)
+) STRICT ;
@@ -0,0 +1,8 @@
+-- Posts hidden from listings, e.g. after deciding not to read them
+CREATE TABLE IF NOT EXISTS hidden
+(
+    instance TEXT NOT NULL,
+    id TEXT NOT NULL,
+    hidden_at INTEGER NOT NULL,
+    PRIMARY KEY (instance, id)
+) STRICT ;
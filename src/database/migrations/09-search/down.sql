@@ -0,0 +1,5 @@
+DROP TRIGGER IF EXISTS seen_insert;
+DROP TRIGGER IF EXISTS seen_delete;
+DROP TRIGGER IF EXISTS seen_update;
+DROP TABLE IF EXISTS seen_fts;
+DROP TABLE IF EXISTS seen;
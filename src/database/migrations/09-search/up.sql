@@ -0,0 +1,93 @@
+-- Posts and comments fetched from each instance, to search everything seen.
+-- Posts are keyed by their short ID and comments by their URL, with comments
+-- titled after the post they were made on
+CREATE TABLE IF NOT EXISTS seen
+(
+    id INTEGER PRIMARY KEY,
+    instance TEXT NOT NULL,
+    key TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    author TEXT NOT NULL,
+    tags TEXT NOT NULL,
+    -- The whole post or comment, stored as JSON
+    data TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE (instance, key)
+) STRICT ;
+
+-- Full-text index of the posts and comments seen, kept up to date by triggers
+CREATE VIRTUAL TABLE IF NOT EXISTS seen_fts USING fts5
+(
+    title,
+    body,
+    author,
+    tags,
+    content = 'seen',
+    content_rowid = 'id',
+    tokenize = 'porter unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS seen_insert AFTER INSERT ON seen
+BEGIN
+    INSERT INTO seen_fts (rowid, title, body, author, tags)
+    VALUES (new.id, new.title, new.body, new.author, new.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS seen_delete AFTER DELETE ON seen
+BEGIN
+    INSERT INTO seen_fts (seen_fts, rowid, title, body, author, tags)
+    VALUES ('delete', old.id, old.title, old.body, old.author, old.tags);
+END;
+
+CREATE TRIGGER IF NOT EXISTS seen_update AFTER UPDATE ON seen
+BEGIN
+    INSERT INTO seen_fts (seen_fts, rowid, title, body, author, tags)
+    VALUES ('delete', old.id, old.title, old.body, old.author, old.tags);
+    INSERT INTO seen_fts (rowid, title, body, author, tags)
+    VALUES (new.id, new.title, new.body, new.author, new.tags);
+END;
+
+-- Index the posts already stored - cached in listings, read or saved
+INSERT INTO seen (instance, key, kind, title, body, author, tags, data, created_at)
+SELECT stored.instance,
+       post.value ->> '$.short_id',
+       'post',
+       post.value ->> '$.title',
+       coalesce(post.value ->> '$.url', ''),
+       coalesce(post.value ->> '$.submitter_user', ''),
+       coalesce((SELECT group_concat(tag.value, ' ') FROM json_each(post.value, '$.tags') AS tag), ''),
+       post.value,
+       coalesce(unixepoch(post.value ->> '$.created_at'), 0)
+FROM (
+    SELECT instance, data FROM listings
+    UNION ALL
+    SELECT instance, json_array(json(data)) FROM posts WHERE data IS NOT NULL
+    UNION ALL
+    SELECT instance, json_array(json(data)) FROM saved
+) AS stored, json_each(stored.data) AS post
+WHERE post.value ->> '$.short_id' IS NOT NULL AND post.value ->> '$.title' IS NOT NULL
+ON CONFLICT DO NOTHING;
+
+-- And the comments cached in listings or with the details of posts
+INSERT INTO seen (instance, key, kind, title, body, author, tags, data, created_at)
+SELECT stored.instance,
+       comment.value ->> '$.url',
+       'comment',
+       coalesce(
+           (SELECT title FROM seen WHERE seen.instance = stored.instance AND seen.key = stored.post),
+           ''
+       ),
+       comment.value ->> '$.comment_plain',
+       coalesce(comment.value ->> '$.commenting_user', ''),
+       '',
+       comment.value,
+       coalesce(unixepoch(comment.value ->> '$.created_at'), 0)
+FROM (
+    SELECT instance, NULL AS post, data FROM listings
+    UNION ALL
+    SELECT instance, id, data -> '$.comments' FROM post_details
+) AS stored, json_each(stored.data) AS comment
+WHERE comment.value ->> '$.url' IS NOT NULL AND comment.value ->> '$.comment_plain' IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -56,13 +56,380 @@ pub fn init_db(path_db: &Path) -> Result<DbPool> {
     Ok(pool)
 }
 
+/// Initialise a database kept in memory, for tests. There is only one
+/// connection, as each connection would get a database of its own.
+#[cfg(test)]
+pub fn init_test_db() -> DbPool {
+    let pool = Pool::builder()
+        .max_size(1)
+        .build(SqliteConnectionManager::memory())
+        .unwrap();
+    MIGRATIONS
+        .to_latest(&mut get_db_connection(&pool).unwrap())
+        .unwrap();
+
+    pool
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::{Post, PostDetails, Validators, test_comment, test_post};
 
     #[test]
     fn test_migrations() {
         assert!(MIGRATIONS.validate().is_ok());
         // insta::assert_debug_snapshot!(MIGRATIONS);
     }
+
+    #[test]
+    fn read_posts_per_instance() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        MIGRATIONS.to_latest(&mut conn).unwrap();
+
+        let is_read = |instance: &str| {
+            conn.query_row(
+                include_str!("./queries/get_post.sql"),
+                [instance, "abc123"],
+                |_| Ok(()),
+            )
+            .is_ok()
+        };
+
+        queries::mark_post_read(&conn, "https://lobste.rs", &test_post("abc123")).unwrap();
+        assert!(is_read("https://lobste.rs"));
+        assert!(!is_read("http://localhost:3000"));
+
+        queries::mark_post_unread(&conn, "https://lobste.rs", "abc123").unwrap();
+        assert!(!is_read("https://lobste.rs"));
+    }
+
+    #[test]
+    fn cache_per_instance() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        MIGRATIONS.to_latest(&mut conn).unwrap();
+
+        let path = "/newest/page/1.json";
+        let validators = crate::data::Validators {
+            etag: Some(String::from("\"abc\"")),
+            last_modified: None,
+        };
+        queries::store_listing(
+            &conn,
+            "https://lobste.rs",
+            path,
+            &vec!["abc123"],
+            &Default::default(),
+        )
+        .unwrap();
+        queries::store_listing(
+            &conn,
+            "https://lobste.rs",
+            path,
+            &vec!["def456"],
+            &validators,
+        )
+        .unwrap();
+
+        let cached = queries::get_listing::<Vec<String>>(&conn, "https://lobste.rs", path)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.data, vec!["def456"]);
+        assert!(cached.fetched_at.timestamp() > 0);
+        assert_eq!(
+            queries::get_listing_validators(&conn, "https://lobste.rs", path).unwrap(),
+            Some(validators.clone())
+        );
+
+        assert!(
+            queries::get_listing::<Vec<String>>(&conn, "http://localhost:3000", path)
+                .unwrap()
+                .is_none()
+        );
+
+        let details = crate::data::PostDetails {
+            short_id: String::from("abc123"),
+            comments: Vec::new(),
+        };
+        queries::store_post_details(&conn, "https://lobste.rs", &details, &validators).unwrap();
+        queries::touch_post_details(&conn, "https://lobste.rs", "abc123").unwrap();
+        let cached = queries::get_post_details(&conn, "https://lobste.rs", "abc123")
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.data.short_id, "abc123");
+        assert_eq!(
+            queries::get_post_details_validators(&conn, "https://lobste.rs", "abc123").unwrap(),
+            Some(validators)
+        );
+        assert!(
+            queries::get_post_details(&conn, "http://localhost:3000", "abc123")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn bandwidth_totals() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        MIGRATIONS.to_latest(&mut conn).unwrap();
+
+        let bandwidth = crate::data::Bandwidth {
+            listings: 1_000,
+            comments: 0,
+            prefetched: 1_000,
+        };
+        queries::add_bandwidth(&conn, "https://lobste.rs", &bandwidth).unwrap();
+        let bandwidth = crate::data::Bandwidth {
+            listings: 0,
+            comments: 500,
+            prefetched: 0,
+        };
+        queries::add_bandwidth(&conn, "https://lobste.rs", &bandwidth).unwrap();
+        // A previous day
+        conn.execute(
+            "INSERT INTO bandwidth (instance, day, listings) VALUES (?, '2020-01-01', 4000)",
+            ["https://lobste.rs"],
+        )
+        .unwrap();
+
+        let expected = crate::data::Bandwidth {
+            listings: 1_000,
+            comments: 500,
+            prefetched: 1_000,
+        };
+        assert_eq!(
+            queries::get_bandwidth_today(&conn, "https://lobste.rs").unwrap(),
+            expected
+        );
+        assert_eq!(
+            queries::get_bandwidth_all_time(&conn, "https://lobste.rs").unwrap(),
+            crate::data::Bandwidth {
+                listings: 5_000,
+                ..expected
+            }
+        );
+        assert_eq!(
+            queries::get_bandwidth_all_time(&conn, "http://localhost:3000").unwrap(),
+            Default::default()
+        );
+    }
+
+    #[test]
+    fn read_history() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        MIGRATIONS.to_latest(&mut conn).unwrap();
+
+        let instance = "https://lobste.rs";
+        for (id, read_at) in [("abc123", 100), ("def456", 300), ("ghi789", 200)] {
+            queries::mark_post_read(&conn, instance, &test_post(id)).unwrap();
+            conn.execute(
+                "UPDATE posts SET read_at = ? WHERE id = ?",
+                rusqlite::params![read_at, id],
+            )
+            .unwrap();
+        }
+        // Reading again keeps when it was first read
+        queries::mark_post_read(&conn, instance, &test_post("abc123")).unwrap();
+        queries::mark_post_read(&conn, "http://localhost:3000", &test_post("xyz000")).unwrap();
+
+        let ids = |page| {
+            queries::get_read_posts(&conn, instance, page)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.short_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(0), vec!["def456", "ghi789", "abc123"]);
+        assert!(ids(1).is_empty());
+
+        queries::mark_post_unread(&conn, instance, "def456").unwrap();
+        assert_eq!(ids(0), vec!["ghi789", "abc123"]);
+
+        // Read before the posts themselves were stored - stored once listed
+        conn.execute(
+            "INSERT INTO posts (instance, id) VALUES (?, 'jkl012')",
+            [instance],
+        )
+        .unwrap();
+        assert_eq!(ids(0), vec!["ghi789", "abc123"]);
+        let mut posts = vec![test_post("jkl012")];
+        queries::update_posts(&conn, instance, &mut posts, false).unwrap();
+        assert!(posts[0].is_read);
+        // Not known when it was read
+        assert_eq!(ids(0), vec!["ghi789", "abc123", "jkl012"]);
+    }
+
+    #[test]
+    fn saved_posts() {
+        use crate::modes::SavedSort;
+
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        MIGRATIONS.to_latest(&mut conn).unwrap();
+
+        let instance = "https://lobste.rs";
+        for (id, title, score, saved_at) in [
+            ("abc123", "beta", 5, 100),
+            ("def456", "Alpha", 1, 300),
+            ("ghi789", "gamma", 20, 200),
+        ] {
+            let post = Post {
+                title: String::from(title),
+                score,
+                ..test_post(id)
+            };
+            queries::save_post(&conn, instance, &post).unwrap();
+            conn.execute(
+                "UPDATE saved SET saved_at = ? WHERE id = ?",
+                rusqlite::params![saved_at, id],
+            )
+            .unwrap();
+        }
+
+        let ids = |sort| {
+            queries::get_saved_posts(&conn, instance, sort, 0)
+                .unwrap()
+                .into_iter()
+                .map(|p| p.short_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(SavedSort::Date), vec!["def456", "ghi789", "abc123"]);
+        assert_eq!(ids(SavedSort::Score), vec!["ghi789", "abc123", "def456"]);
+        assert_eq!(ids(SavedSort::Title), vec!["def456", "abc123", "ghi789"]);
+
+        // Separate from read state
+        let mut posts = vec![test_post("abc123"), test_post("xyz000")];
+        queries::update_posts(&conn, instance, &mut posts, false).unwrap();
+        assert!(posts[0].is_saved && !posts[0].is_read);
+        assert!(!posts[1].is_saved);
+
+        queries::unsave_post(&conn, instance, "abc123").unwrap();
+        assert_eq!(ids(SavedSort::Date), vec!["def456", "ghi789"]);
+    }
+
+    #[test]
+    fn hidden_posts() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        MIGRATIONS.to_latest(&mut conn).unwrap();
+
+        let instance = "https://lobste.rs";
+        queries::hide_post(&conn, instance, "def456").unwrap();
+        queries::hide_post(&conn, "http://localhost:3000", "abc123").unwrap();
+
+        let listing = || {
+            vec![
+                test_post("abc123"),
+                test_post("def456"),
+                test_post("ghi789"),
+            ]
+        };
+        let ids = |posts: &[Post]| posts.iter().map(|p| p.short_id.clone()).collect::<Vec<_>>();
+
+        let mut posts = listing();
+        assert_eq!(
+            queries::update_posts(&conn, instance, &mut posts, false).unwrap(),
+            1
+        );
+        assert_eq!(ids(&posts), vec!["abc123", "ghi789"]);
+
+        let mut posts = listing();
+        assert_eq!(
+            queries::update_posts(&conn, instance, &mut posts, true).unwrap(),
+            1
+        );
+        assert_eq!(ids(&posts), vec!["abc123", "def456", "ghi789"]);
+        assert!(posts[1].is_hidden);
+
+        queries::unhide_post(&conn, instance, "def456").unwrap();
+        let mut posts = listing();
+        assert_eq!(
+            queries::update_posts(&conn, instance, &mut posts, false).unwrap(),
+            0
+        );
+        assert_eq!(posts.len(), 3);
+    }
+
+    #[test]
+    fn search_seen() {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        let instance = "https://lobste.rs";
+        let comment = |id: &str, text: &str| {
+            test_comment(&format!("{instance}/s/abc123/notes_on#c_{id}"), text)
+        };
+
+        // Stored before anything was indexed
+        MIGRATIONS.to_version(&mut conn, 8).unwrap();
+        let mut io_uring = test_post("abc123");
+        io_uring.title = String::from("Notes on io_uring");
+        let listing = vec![io_uring, test_post("def456")];
+        queries::store_listing(&conn, instance, "/page/1", &listing, &Validators::default())
+            .unwrap();
+        let details = PostDetails {
+            short_id: String::from("abc123"),
+            comments: vec![comment("a", "Faster than epoll")],
+        };
+        queries::store_post_details(&conn, instance, &details, &Validators::default()).unwrap();
+        MIGRATIONS.to_latest(&mut conn).unwrap();
+
+        let mut epoll = test_post("xyz000");
+        epoll.title = String::from("Why epoll is broken");
+        queries::index_posts(&conn, instance, &[epoll, test_post("def456")]).unwrap();
+        queries::index_posts(&conn, "http://localhost:3000", &[test_post("ghi789")]).unwrap();
+        queries::index_comments(&conn, instance, &[comment("b", "We switched last month")])
+            .unwrap();
+
+        let search = |query| queries::search_seen(&conn, instance, query, 10).unwrap();
+        let keys = |query| {
+            search(query)
+                .into_iter()
+                .map(|hit| match hit.seen {
+                    queries::Seen::Post(post) => post.short_id,
+                    queries::Seen::Comment(comment) => {
+                        comment.url.rsplit('_').next().unwrap().into()
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Matches in titles rank higher
+        assert_eq!(keys("epoll"), vec!["xyz000", "a"]);
+        // Comments are titled after the post they were made on
+        assert_eq!(search("epoll")[1].title, "Notes on io_uring");
+        assert_eq!(keys("switched notes"), vec!["b"]);
+        // The last word is a prefix
+        assert_eq!(keys("swit"), vec!["b"]);
+        assert_eq!(search("faster")[0].snippet, "\u{2}Faster\u{3} than epoll");
+
+        assert_eq!(keys("def456"), vec!["def456"]);
+        assert!(keys("ghi789").is_empty());
+        assert!(keys(" ").is_empty());
+        assert!(keys("\" OR * (").is_empty());
+
+        // Listed comments get the title of the post they were made on
+        let mut comments = vec![
+            comment("c", ""),
+            test_comment("https://lobste.rs/s/zzz999", ""),
+        ];
+        queries::update_comments(&conn, instance, &mut comments).unwrap();
+        assert_eq!(comments[0].post_title.as_deref(), Some("Notes on io_uring"));
+        assert_eq!(comments[1].post_title, None);
+
+        // Titled after the post once it is seen
+        let hit = |query| search(query).remove(0).title;
+        queries::index_comments(
+            &conn,
+            instance,
+            &[
+                test_comment(&format!("{instance}/s/zzz999/kernel_bypass#c_d"), "DPDK"),
+                test_comment(&format!("{instance}/s/zzz9990/ebpf#c_e"), "XDP"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(hit("dpdk"), "kernel bypass");
+        let mut post = test_post("zzz999");
+        post.title = String::from("Kernel bypass networking");
+        queries::index_posts(&conn, instance, &[post]).unwrap();
+        assert_eq!(hit("dpdk"), "Kernel bypass networking");
+        // Made on another post whose ID starts the same way
+        assert_eq!(hit("xdp"), "ebpf");
+    }
 }
@@ -0,0 +1,7 @@
+INSERT
+INTO bandwidth (instance, day, listings, comments, prefetched)
+VALUES (?, date('now', 'localtime'), ?, ?, ?)
+ON CONFLICT (instance, day) DO UPDATE
+SET listings = listings + excluded.listings,
+    comments = comments + excluded.comments,
+    prefetched = prefetched + excluded.prefetched
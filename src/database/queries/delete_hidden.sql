@@ -0,0 +1 @@
+DELETE FROM hidden WHERE instance = ? AND id = ?
@@ -1 +1 @@
-DELETE FROM posts WHERE id = ?
+DELETE FROM posts WHERE instance = ? AND id = ?
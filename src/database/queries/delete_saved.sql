@@ -0,0 +1 @@
+DELETE FROM saved WHERE instance = ? AND id = ?
@@ -0,0 +1,3 @@
+SELECT coalesce(sum(listings), 0), coalesce(sum(comments), 0), coalesce(sum(prefetched), 0)
+FROM bandwidth
+WHERE instance = ?
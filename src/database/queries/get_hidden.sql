@@ -0,0 +1 @@
+SELECT 1 FROM hidden WHERE instance = ? AND id = ?
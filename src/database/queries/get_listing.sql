@@ -0,0 +1 @@
+SELECT data, fetched_at FROM listings WHERE instance = ? AND path = ?
@@ -0,0 +1 @@
+SELECT etag, last_modified FROM listings WHERE instance = ? AND path = ?
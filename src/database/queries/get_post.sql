@@ -1 +1 @@
-SELECT * FROM posts WHERE id = ?
+SELECT * FROM posts WHERE instance = ? AND id = ?
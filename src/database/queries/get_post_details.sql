@@ -0,0 +1 @@
+SELECT data, fetched_at FROM post_details WHERE instance = ? AND id = ?
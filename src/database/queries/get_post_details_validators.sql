@@ -0,0 +1 @@
+SELECT etag, last_modified FROM post_details WHERE instance = ? AND id = ?
@@ -0,0 +1,6 @@
+SELECT data
+FROM posts
+WHERE instance = ? AND data IS NOT NULL
+-- Posts read before it was recorded when are listed last
+ORDER BY read_at IS NULL, read_at DESC
+LIMIT ? OFFSET ?
@@ -0,0 +1 @@
+SELECT 1 FROM saved WHERE instance = ? AND id = ?
@@ -0,0 +1,5 @@
+SELECT data
+FROM saved
+WHERE instance = ?
+ORDER BY data ->> '$.score' DESC, saved_at DESC
+LIMIT ? OFFSET ?
@@ -0,0 +1,5 @@
+SELECT data
+FROM saved
+WHERE instance = ?
+ORDER BY data ->> '$.title' COLLATE NOCASE, saved_at DESC
+LIMIT ? OFFSET ?
@@ -0,0 +1,3 @@
+SELECT title
+FROM seen
+WHERE instance = ? AND key = ? AND kind = 'post'
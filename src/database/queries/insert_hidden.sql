@@ -0,0 +1,4 @@
+INSERT
+INTO hidden (instance, id, hidden_at)
+VALUES (?, ?, unixepoch())
+ON CONFLICT (instance, id) DO NOTHING
@@ -1,4 +1,10 @@
 INSERT
-INTO posts (id)
-VALUES (?)
-ON CONFLICT (id) DO NOTHING
+INTO posts (instance, id, read_at, title, url, tags, submitter, data)
+VALUES (?, ?, unixepoch(), ?, ?, ?, ?, ?)
+ON CONFLICT (instance, id) DO UPDATE
+SET read_at = coalesce(read_at, excluded.read_at),
+    title = excluded.title,
+    url = excluded.url,
+    tags = excluded.tags,
+    submitter = excluded.submitter,
+    data = excluded.data
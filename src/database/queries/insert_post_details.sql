@@ -0,0 +1,8 @@
+INSERT
+INTO post_details (instance, id, data, etag, last_modified, fetched_at)
+VALUES (?, ?, ?, ?, ?, unixepoch())
+ON CONFLICT (instance, id) DO UPDATE
+SET data = excluded.data,
+    etag = excluded.etag,
+    last_modified = excluded.last_modified,
+    fetched_at = excluded.fetched_at
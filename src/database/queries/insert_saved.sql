@@ -0,0 +1,5 @@
+INSERT
+INTO saved (instance, id, data, saved_at)
+VALUES (?, ?, ?, unixepoch())
+ON CONFLICT (instance, id) DO UPDATE
+SET data = excluded.data
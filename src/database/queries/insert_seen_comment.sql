@@ -0,0 +1,19 @@
+INSERT
+INTO seen (instance, key, kind, title, body, author, tags, data, created_at)
+VALUES (
+    ?1,
+    ?2,
+    'comment',
+    coalesce((SELECT title FROM seen WHERE instance = ?1 AND key = ?3), ?4),
+    ?5,
+    ?6,
+    '',
+    ?7,
+    ?8
+)
+ON CONFLICT (instance, key) DO UPDATE
+SET title = excluded.title,
+    body = excluded.body,
+    author = excluded.author,
+    data = excluded.data
+WHERE data IS NOT excluded.data OR title IS NOT excluded.title
@@ -0,0 +1,10 @@
+INSERT
+INTO seen (instance, key, kind, title, body, author, tags, data, created_at)
+VALUES (?, ?, 'post', ?, ?, ?, ?, ?, ?)
+ON CONFLICT (instance, key) DO UPDATE
+SET title = excluded.title,
+    body = excluded.body,
+    author = excluded.author,
+    tags = excluded.tags,
+    data = excluded.data
+WHERE data IS NOT excluded.data
@@ -1,17 +1,151 @@
+use chrono::{DateTime, Utc};
 use color_eyre::{Result, eyre::Context};
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Serialize, de::DeserializeOwned};
 
-use crate::data::Post;
+use crate::{data::{Bandwidth, Post, PostComment, PostDetails, Validators}, modes::SavedSort};
 
-pub fn mark_post_read(conn: &Connection, id: impl AsRef<str>) -> Result<()> {
-    conn.execute(include_str!("./insert_post.sql"), params![id.as_ref()])
-        .map(|_| ())
-        .context("failed to execute: insert post")
+/// Data cached in the database, along with when it was fetched.
+#[derive(Debug)]
+pub struct Cached<T> {
+    pub data: T,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Post or comment seen on an instance.
+#[derive(Debug, Clone)]
+pub enum Seen {
+    Post(Post),
+    Comment(PostComment),
+}
+
+/// Post or comment found by searching everything seen, along with a snippet of
+/// where it matched.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub seen: Seen,
+    /// Title of the post, or of the post the comment was made on (if known)
+    pub title: String,
+    /// Text around the match, with the matching terms between
+    /// [`SNIPPET_MATCH_START`] and [`SNIPPET_MATCH_END`]
+    pub snippet: String,
+}
+
+/// Marks the start of a matching term in the snippet of a [`SearchHit`].
+pub const SNIPPET_MATCH_START: char = '\u{2}';
+/// Marks the end of a matching term in the snippet of a [`SearchHit`].
+pub const SNIPPET_MATCH_END: char = '\u{3}';
+
+/// Number of posts in each page of the posts stored locally, i.e. the history
+/// and saved posts.
+pub const LOCAL_PAGE_SIZE: usize = 25;
+
+/// Mark a post read, recording when it was first read along with the post
+/// itself so it can be listed in the history.
+pub fn mark_post_read(conn: &Connection, instance: &str, post: &Post) -> Result<()> {
+    let data = serde_json::to_string(post).context("failed to serialise post")?;
+    conn.execute(
+        include_str!("./insert_post.sql"),
+        params![
+            instance,
+            post.short_id,
+            post.title,
+            post.url,
+            post.tags.join(" "),
+            post.submitter_user,
+            data
+        ],
+    )
+    .map(|_| ())
+    .context("failed to execute: insert post")
+}
+
+/// Store a post which was marked read without it, keeping when it was read
+/// (if that is known).
+fn store_read_post(conn: &Connection, instance: &str, post: &Post) -> Result<()> {
+    let data = serde_json::to_string(post).context("failed to serialise post")?;
+    conn.execute(
+        include_str!("./update_post_data.sql"),
+        params![
+            post.title,
+            post.url,
+            post.tags.join(" "),
+            post.submitter_user,
+            data,
+            instance,
+            post.short_id
+        ],
+    )
+    .map(|_| ())
+    .context("failed to execute: update post data")
+}
+
+/// Get the given (0-indexed) page of the posts read on an instance, most
+/// recently read first. Posts read before it was recorded when are last.
+pub fn get_read_posts(conn: &Connection, instance: &str, page: usize) -> Result<Vec<Post>> {
+    get_posts_page(conn, include_str!("./get_read_posts.sql"), instance, page)
+        .context("failed to get read posts from the database")
+}
+
+/// Save a post to read later, separately from whether it has been read.
+pub fn save_post(conn: &Connection, instance: &str, post: &Post) -> Result<()> {
+    let data = serde_json::to_string(post).context("failed to serialise post")?;
+    conn.execute(
+        include_str!("./insert_saved.sql"),
+        params![instance, post.short_id, data],
+    )
+    .map(|_| ())
+    .context("failed to execute: insert saved post")
+}
+
+pub fn unsave_post(conn: &Connection, instance: &str, id: impl AsRef<str>) -> Result<()> {
+    conn.execute(
+        include_str!("./delete_saved.sql"),
+        params![instance, id.as_ref()],
+    )
+    .map(|_| ())
+    .context("failed to execute: delete saved post")
+}
+
+/// Get the given (0-indexed) page of the posts saved on an instance, in the
+/// given order.
+pub fn get_saved_posts(
+    conn: &Connection,
+    instance: &str,
+    sort: SavedSort,
+    page: usize,
+) -> Result<Vec<Post>> {
+    let query = match sort {
+        SavedSort::Date => include_str!("./get_saved_posts_by_date.sql"),
+        SavedSort::Score => include_str!("./get_saved_posts_by_score.sql"),
+        SavedSort::Title => include_str!("./get_saved_posts_by_title.sql"),
+    };
+
+    get_posts_page(conn, query, instance, page)
+        .context("failed to get saved posts from the database")
+}
+
+fn get_posts_page(
+    conn: &Connection,
+    query: &str,
+    instance: &str,
+    page: usize,
+) -> Result<Vec<Post>> {
+    let mut stmt = conn.prepare(query)?;
+    let rows = stmt.query_map(
+        params![instance, LOCAL_PAGE_SIZE, page * LOCAL_PAGE_SIZE],
+        |row| row.get::<_, String>(0),
+    )?;
+
+    rows.map(|data| serde_json::from_str(&data?).context("failed to deserialise post"))
+        .collect()
 }
 
-pub fn mark_post_unread(conn: &Connection, id: impl AsRef<str>) -> Result<()> {
-    if let Err(e) = conn.execute(include_str!("./delete_post.sql"), params![id.as_ref()])
-        && !matches!(e, rusqlite::Error::QueryReturnedNoRows)
+pub fn mark_post_unread(conn: &Connection, instance: &str, id: impl AsRef<str>) -> Result<()> {
+    if let Err(e) = conn.execute(
+        include_str!("./delete_post.sql"),
+        params![instance, id.as_ref()],
+    ) && !matches!(e, rusqlite::Error::QueryReturnedNoRows)
     {
         return Err(e).context("failed to execute: delete post");
     }
@@ -19,14 +153,81 @@ pub fn mark_post_unread(conn: &Connection, id: impl AsRef<str>) -> Result<()> {
     Ok(())
 }
 
-pub fn update_posts(conn: &Connection, posts: &mut [Post]) -> Result<()> {
+/// Hide a post from listings.
+pub fn hide_post(conn: &Connection, instance: &str, id: impl AsRef<str>) -> Result<()> {
+    conn.execute(
+        include_str!("./insert_hidden.sql"),
+        params![instance, id.as_ref()],
+    )
+    .map(|_| ())
+    .context("failed to execute: insert hidden post")
+}
+
+pub fn unhide_post(conn: &Connection, instance: &str, id: impl AsRef<str>) -> Result<()> {
+    conn.execute(
+        include_str!("./delete_hidden.sql"),
+        params![instance, id.as_ref()],
+    )
+    .map(|_| ())
+    .context("failed to execute: delete hidden post")
+}
+
+/// Update the posts with their state stored in the database, dropping those
+/// which have been hidden unless `show_hidden` is set. Returns the number of
+/// hidden posts, whether dropped or not.
+///
+/// Read posts which were stored without their data are stored again with it.
+pub fn update_posts(
+    conn: &Connection,
+    instance: &str,
+    posts: &mut Vec<Post>,
+    show_hidden: bool,
+) -> Result<usize> {
+    let mut stmt_hidden = conn
+        .prepare(include_str!("./get_hidden.sql"))
+        .context("failed to prepare: get hidden post")?;
+
+    let mut hidden = 0;
+    for p in posts.iter_mut() {
+        p.is_hidden = stmt_hidden
+            .exists(params![instance, p.short_id.as_str()])
+            .context(format!(
+                "error getting hidden post from the database: '{}'",
+                p.title
+            ))?;
+        hidden += usize::from(p.is_hidden);
+    }
+    if !show_hidden {
+        posts.retain(|p| !p.is_hidden);
+    }
+
     let mut stmt = conn
         .prepare(include_str!("./get_post.sql"))
         .context("failed to prepare: get post")?;
+    let mut stmt_saved = conn
+        .prepare(include_str!("./get_saved.sql"))
+        .context("failed to prepare: get saved post")?;
 
     for p in posts.iter_mut() {
-        match stmt.query_one(params![p.short_id.as_str()], |_| Ok(())) {
-            Ok(_) => p.is_read = true,
+        p.is_saved = stmt_saved
+            .exists(params![instance, p.short_id.as_str()])
+            .context(format!(
+                "error getting saved post from the database: '{}'",
+                p.title
+            ))?;
+
+        let data = stmt.query_one(params![instance, p.short_id.as_str()], |row| {
+            row.get::<_, Option<String>>("data")
+        });
+        match data {
+            Ok(data) => {
+                p.is_read = true;
+                // Read before the posts themselves were stored - store it now so
+                // it's listed in the history
+                if data.is_none() {
+                    store_read_post(conn, instance, p)?;
+                }
+            }
             Err(e) => {
                 if matches!(e, rusqlite::Error::QueryReturnedNoRows) {
                     continue;
@@ -40,5 +241,340 @@ pub fn update_posts(conn: &Connection, posts: &mut [Post]) -> Result<()> {
         }
     }
 
+    Ok(hidden)
+}
+
+/// Update the comments with the titles of the posts they were made on, for
+/// those posts which have been seen.
+pub fn update_comments(
+    conn: &Connection,
+    instance: &str,
+    comments: &mut [PostComment],
+) -> Result<()> {
+    let mut stmt = conn
+        .prepare(include_str!("./get_seen_post_title.sql"))
+        .context("failed to prepare: get seen post title")?;
+
+    for c in comments.iter_mut() {
+        let Some(id) = c.story_id(instance) else {
+            continue;
+        };
+        c.post_title = stmt
+            .query_one(params![instance, id], |row| row.get(0))
+            .optional()
+            .context("error getting the title of a seen post from the database")?;
+    }
+
     Ok(())
 }
+
+/// Index posts fetched from an instance, to find them by searching everything
+/// seen. Posts seen before are updated, and comments seen on them are titled
+/// after them.
+pub fn index_posts(conn: &Connection, instance: &str, posts: &[Post]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx
+            .prepare(include_str!("./insert_seen_post.sql"))
+            .context("failed to prepare: insert seen post")?;
+        for post in posts {
+            let data = serde_json::to_string(post).context("failed to serialise post")?;
+            stmt.execute(params![
+                instance,
+                post.short_id,
+                post.title,
+                post.url,
+                post.submitter_user,
+                post.tags.join(" "),
+                data,
+                post.created_at.timestamp()
+            ])
+            .context("failed to execute: insert seen post")?;
+        }
+
+        // Story URLs of the posts, along with their titles
+        let stories: Vec<(String, &str)> = posts
+            .iter()
+            .map(|p| (format!("{instance}/s/{}", p.short_id), p.title.as_str()))
+            .collect();
+        let stories = serde_json::to_string(&stories).context("failed to serialise stories")?;
+        tx.execute(
+            include_str!("./update_seen_comment_titles.sql"),
+            params![instance, stories],
+        )
+        .context("failed to execute: update seen comment titles")?;
+    }
+
+    tx.commit().context("failed to commit: index posts")
+}
+
+/// Index comments fetched from an instance, to find them by searching
+/// everything seen. They are titled after the post they were made on, if it has
+/// been seen.
+pub fn index_comments(conn: &Connection, instance: &str, comments: &[PostComment]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx
+            .prepare(include_str!("./insert_seen_comment.sql"))
+            .context("failed to prepare: insert seen comment")?;
+        for comment in comments {
+            let data = serde_json::to_string(comment).context("failed to serialise comment")?;
+            stmt.execute(params![
+                instance,
+                comment.url,
+                comment.story_id(instance),
+                comment.story_title(instance).unwrap_or_default(),
+                comment.comment_plain,
+                comment.commenting_user,
+                data,
+                comment.created_at.timestamp()
+            ])
+            .context("failed to execute: insert seen comment")?;
+        }
+    }
+
+    tx.commit().context("failed to commit: index comments")
+}
+
+/// Search the posts and comments seen on an instance for every word of the
+/// query (or words starting with the last one), best matches first.
+pub fn search_seen(
+    conn: &Connection,
+    instance: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchHit>> {
+    // Quote each word, so characters which are part of the query syntax are
+    // searched for instead
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    let Some(last) = words.last() else {
+        return Ok(Vec::new());
+    };
+    let query = format!("{} {last}*", words[..words.len() - 1].join(" "));
+
+    let mut stmt = conn
+        .prepare(include_str!("./search_seen.sql"))
+        .context("failed to prepare: search seen")?;
+    let rows = stmt
+        .query_map(params![query, instance, limit], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .context("failed to search seen posts and comments")?;
+
+    rows.map(|row| {
+        let (kind, title, data, snippet) = row?;
+        let seen = match kind.as_str() {
+            "comment" => {
+                Seen::Comment(serde_json::from_str(&data).context("failed to deserialise comment")?)
+            }
+            _ => Seen::Post(serde_json::from_str(&data).context("failed to deserialise post")?),
+        };
+
+        Ok(SearchHit {
+            seen,
+            title,
+            snippet,
+        })
+    })
+    .collect()
+}
+
+/// Store the results fetched for a listing, identified by its path relative to
+/// the base URL of the instance (e.g. `/newest/page/2.json`).
+pub fn store_listing(
+    conn: &Connection,
+    instance: &str,
+    path: &str,
+    data: &impl Serialize,
+    validators: &Validators,
+) -> Result<()> {
+    let data = serde_json::to_string(data).context("failed to serialise listing")?;
+    conn.execute(
+        include_str!("./insert_listing.sql"),
+        params![
+            instance,
+            path,
+            data,
+            validators.etag,
+            validators.last_modified
+        ],
+    )
+    .map(|_| ())
+    .context("failed to execute: insert listing")
+}
+
+/// Get the last results fetched for a listing, if it has been fetched before.
+pub fn get_listing<T: DeserializeOwned>(
+    conn: &Connection,
+    instance: &str,
+    path: &str,
+) -> Result<Option<Cached<T>>> {
+    get_cached(conn, include_str!("./get_listing.sql"), instance, path)
+        .context("failed to get listing from the database")
+}
+
+/// Validators for the last results fetched for a listing, if it has been
+/// fetched before.
+pub fn get_listing_validators(
+    conn: &Connection,
+    instance: &str,
+    path: &str,
+) -> Result<Option<Validators>> {
+    get_validators(
+        conn,
+        include_str!("./get_listing_validators.sql"),
+        instance,
+        path,
+    )
+    .context("failed to get listing validators from the database")
+}
+
+/// Mark the last results fetched for a listing as being up to date.
+pub fn touch_listing(conn: &Connection, instance: &str, path: &str) -> Result<()> {
+    conn.execute(include_str!("./touch_listing.sql"), params![instance, path])
+        .map(|_| ())
+        .context("failed to execute: touch listing")
+}
+
+pub fn store_post_details(
+    conn: &Connection,
+    instance: &str,
+    details: &PostDetails,
+    validators: &Validators,
+) -> Result<()> {
+    let data = serde_json::to_string(details).context("failed to serialise post details")?;
+    conn.execute(
+        include_str!("./insert_post_details.sql"),
+        params![
+            instance,
+            details.short_id,
+            data,
+            validators.etag,
+            validators.last_modified
+        ],
+    )
+    .map(|_| ())
+    .context("failed to execute: insert post details")
+}
+
+/// Get the last details fetched for a post, if they have been fetched before.
+pub fn get_post_details(
+    conn: &Connection,
+    instance: &str,
+    id: impl AsRef<str>,
+) -> Result<Option<Cached<PostDetails>>> {
+    get_cached(
+        conn,
+        include_str!("./get_post_details.sql"),
+        instance,
+        id.as_ref(),
+    )
+    .context("failed to get post details from the database")
+}
+
+/// Validators for the last details fetched for a post, if they have been
+/// fetched before.
+pub fn get_post_details_validators(
+    conn: &Connection,
+    instance: &str,
+    id: impl AsRef<str>,
+) -> Result<Option<Validators>> {
+    get_validators(
+        conn,
+        include_str!("./get_post_details_validators.sql"),
+        instance,
+        id.as_ref(),
+    )
+    .context("failed to get post details validators from the database")
+}
+
+/// Mark the last details fetched for a post as being up to date.
+pub fn touch_post_details(conn: &Connection, instance: &str, id: impl AsRef<str>) -> Result<()> {
+    conn.execute(
+        include_str!("./touch_post_details.sql"),
+        params![instance, id.as_ref()],
+    )
+    .map(|_| ())
+    .context("failed to execute: touch post details")
+}
+
+/// Add bytes downloaded from an instance to the totals for today.
+pub fn add_bandwidth(conn: &Connection, instance: &str, bandwidth: &Bandwidth) -> Result<()> {
+    conn.execute(
+        include_str!("./add_bandwidth.sql"),
+        params![
+            instance,
+            bandwidth.listings,
+            bandwidth.comments,
+            bandwidth.prefetched
+        ],
+    )
+    .map(|_| ())
+    .context("failed to execute: add bandwidth")
+}
+
+/// Bytes downloaded from an instance today (in local time).
+pub fn get_bandwidth_today(conn: &Connection, instance: &str) -> Result<Bandwidth> {
+    get_bandwidth(conn, include_str!("./get_bandwidth_today.sql"), instance)
+        .context("failed to get today's bandwidth from the database")
+}
+
+/// Bytes downloaded from an instance across all sessions.
+pub fn get_bandwidth_all_time(conn: &Connection, instance: &str) -> Result<Bandwidth> {
+    get_bandwidth(conn, include_str!("./get_bandwidth_all_time.sql"), instance)
+        .context("failed to get all-time bandwidth from the database")
+}
+
+fn get_bandwidth(conn: &Connection, query: &str, instance: &str) -> rusqlite::Result<Bandwidth> {
+    conn.query_row(query, params![instance], |row| {
+        Ok(Bandwidth {
+            listings: row.get(0)?,
+            comments: row.get(1)?,
+            prefetched: row.get(2)?,
+        })
+    })
+}
+
+fn get_validators(
+    conn: &Connection,
+    query: &str,
+    instance: &str,
+    key: &str,
+) -> rusqlite::Result<Option<Validators>> {
+    conn.query_row(query, params![instance, key], |row| {
+        Ok(Validators {
+            etag: row.get(0)?,
+            last_modified: row.get(1)?,
+        })
+    })
+    .optional()
+}
+
+fn get_cached<T: DeserializeOwned>(
+    conn: &Connection,
+    query: &str,
+    instance: &str,
+    key: &str,
+) -> Result<Option<Cached<T>>> {
+    let Some((data, fetched_at)) = conn
+        .query_row(query, params![instance, key], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .optional()?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(Cached {
+        data: serde_json::from_str(&data).context("failed to deserialise cached data")?,
+        fetched_at: DateTime::from_timestamp(fetched_at, 0).unwrap_or_default(),
+    }))
+}
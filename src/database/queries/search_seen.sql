@@ -0,0 +1,6 @@
+SELECT seen.kind, seen.title, seen.data, snippet(seen_fts, -1, char(2), char(3), '…', 16)
+FROM seen_fts
+JOIN seen ON seen.id = seen_fts.rowid
+WHERE seen_fts MATCH ? AND seen.instance = ?
+ORDER BY bm25(seen_fts, 4.0, 1.0, 2.0, 2.0)
+LIMIT ?
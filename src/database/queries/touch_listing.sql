@@ -0,0 +1 @@
+UPDATE listings SET fetched_at = unixepoch() WHERE instance = ? AND path = ?
@@ -0,0 +1 @@
+UPDATE post_details SET fetched_at = unixepoch() WHERE instance = ? AND id = ?
@@ -0,0 +1,7 @@
+UPDATE posts
+SET title = ?,
+    url = ?,
+    tags = ?,
+    submitter = ?,
+    data = ?
+WHERE instance = ? AND id = ?
@@ -0,0 +1,17 @@
+-- Comments on a story are keyed by its URL followed by '#' or '/', which both
+-- sort before '0' - unlike any other story whose ID starts the same way. Going
+-- through the stories first looks up each one's range of keys in the index
+WITH comment AS MATERIALIZED (
+    SELECT seen.id, story.value ->> 1 AS title
+    FROM json_each(?2) AS story
+    CROSS JOIN seen
+    WHERE seen.instance = ?1
+      AND seen.key >= (story.value ->> 0) || '#'
+      AND seen.key < (story.value ->> 0) || '0'
+      AND seen.kind = 'comment'
+      AND seen.title IS NOT story.value ->> 1
+)
+UPDATE seen
+SET title = comment.title
+FROM comment
+WHERE seen.id = comment.id
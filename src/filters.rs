@@ -0,0 +1,244 @@
+//! Filter rules - match posts by their title, domain, submitter or tags, to
+//! hide, dim or highlight them in listings.
+use std::fmt::Display;
+
+use ratatui::style::Color;
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+use crate::data::Post;
+
+/// Part of a post which a rule is matched against.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterField {
+    Title,
+    Domain,
+    Submitter,
+    /// Any of the post's tags
+    Tag,
+}
+
+impl Display for FilterField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Title => write!(f, "title"),
+            Self::Domain => write!(f, "domain"),
+            Self::Submitter => write!(f, "submitter"),
+            Self::Tag => write!(f, "tag"),
+        }
+    }
+}
+
+/// What to do with posts matched by a rule.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// Drop them from listings
+    Hide,
+    /// List them, dimmed like read posts
+    Dim,
+    /// List them, with the title highlighted
+    Highlight,
+}
+
+impl Display for FilterAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hide => write!(f, "hide"),
+            Self::Dim => write!(f, "dim"),
+            Self::Highlight => write!(f, "highlight"),
+        }
+    }
+}
+
+/// Pattern matched (case insensitively) against a field of a post.
+#[derive(Debug, Clone)]
+pub enum FilterPattern {
+    /// Contained in the title, the domain or one of its parent domains, or
+    /// equal to the other fields
+    Plain(String),
+    /// Matching anywhere in the field, unless anchored
+    Regex(Regex),
+}
+
+impl FilterPattern {
+    fn matches(&self, field: FilterField, value: &str) -> bool {
+        match self {
+            Self::Plain(pattern) if field == FilterField::Title => {
+                value.to_lowercase().contains(pattern)
+            }
+            Self::Plain(pattern) if field == FilterField::Domain => {
+                let value = value.to_lowercase();
+                value == *pattern || value.ends_with(&format!(".{pattern}"))
+            }
+            Self::Plain(pattern) => value.to_lowercase() == *pattern,
+            Self::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl PartialEq for FilterPattern {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Plain(a), Self::Plain(b)) => a == b,
+            (Self::Regex(a), Self::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for FilterPattern {}
+
+impl Display for FilterPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Plain(pattern) => write!(f, "\"{pattern}\""),
+            Self::Regex(regex) => write!(f, "/{}/", regex.as_str()),
+        }
+    }
+}
+
+/// Rule declared in the config, matching posts by one of their fields.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+#[serde(try_from = "RawFilterRule")]
+pub struct FilterRule {
+    pub field: FilterField,
+    pub pattern: FilterPattern,
+    pub action: FilterAction,
+    /// Colour of the titles of highlighted posts
+    pub fg: Color,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFilterRule {
+    field: FilterField,
+    pattern: String,
+    #[serde(default)]
+    regex: bool,
+    action: FilterAction,
+    #[serde(default = "default_fg")]
+    fg: Color,
+}
+
+fn default_fg() -> Color {
+    Color::Yellow
+}
+
+impl TryFrom<RawFilterRule> for FilterRule {
+    type Error = regex::Error;
+
+    fn try_from(raw: RawFilterRule) -> Result<Self, Self::Error> {
+        let pattern = if raw.regex {
+            FilterPattern::Regex(
+                RegexBuilder::new(&raw.pattern)
+                    .case_insensitive(true)
+                    .build()?,
+            )
+        } else {
+            FilterPattern::Plain(raw.pattern.to_lowercase())
+        };
+
+        Ok(Self {
+            field: raw.field,
+            pattern,
+            action: raw.action,
+            fg: raw.fg,
+        })
+    }
+}
+
+impl FilterRule {
+    /// Whether the rule matches the post.
+    pub fn matches(&self, post: &Post) -> bool {
+        let matches = |value: &str| self.pattern.matches(self.field, value);
+
+        match self.field {
+            FilterField::Title => matches(&post.title),
+            FilterField::Domain => post.domain().is_some_and(|d| matches(&d)),
+            FilterField::Submitter => matches(&post.submitter_user),
+            FilterField::Tag => post.tags.iter().any(|t| matches(t)),
+        }
+    }
+}
+
+impl Display for FilterRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.action, self.field, self.pattern)
+    }
+}
+
+/// Index of the first of the rules which matches the post, which is the one
+/// applied to it.
+pub fn matching_rule(rules: &[FilterRule], post: &Post) -> Option<usize> {
+    rules.iter().position(|r| r.matches(post))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::data::test_post;
+
+    fn rule(field: &str, pattern: &str, regex: bool, action: &str) -> FilterRule {
+        let toml = format!(
+            "field = \"{field}\"\npattern = '{pattern}'\nregex = {regex}\naction = \"{action}\""
+        );
+        toml::from_str(&toml).unwrap()
+    }
+
+    fn post() -> Post {
+        let mut post = test_post("abc123");
+        post.title = String::from("Rewriting it in Rust");
+        post.url = String::from("https://blog.example.com/rust");
+        post.submitter_user = String::from("alice");
+        post.tags = vec![String::from("rust"), String::from("programming")];
+        post
+    }
+
+    #[test]
+    fn plain_patterns() {
+        let post = post();
+
+        assert!(rule("title", "in rust", false, "hide").matches(&post));
+        assert!(!rule("title", "golang", false, "hide").matches(&post));
+        // Fields other than the title need to match in full
+        assert!(rule("domain", "Example.com", false, "dim").matches(&post));
+        assert!(rule("domain", "blog.example.com", false, "dim").matches(&post));
+        assert!(!rule("domain", "example", false, "dim").matches(&post));
+        assert!(!rule("domain", "ple.com", false, "dim").matches(&post));
+        assert!(rule("submitter", "alice", false, "highlight").matches(&post));
+        assert!(!rule("submitter", "ali", false, "highlight").matches(&post));
+        assert!(rule("tag", "programming", false, "dim").matches(&post));
+        assert!(!rule("tag", "prog", false, "dim").matches(&post));
+    }
+
+    #[test]
+    fn regex_patterns() {
+        let post = post();
+
+        assert!(rule("title", r"^rewriting\b", true, "hide").matches(&post));
+        assert!(!rule("title", r"^rust", true, "hide").matches(&post));
+        assert!(rule("submitter", "^(alice|bob)$", true, "dim").matches(&post));
+        assert!(rule("tag", "^prog", true, "dim").matches(&post));
+
+        let invalid: Result<FilterRule, _> =
+            toml::from_str("field = \"title\"\npattern = '('\nregex = true\naction = \"hide\"");
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn first_matching_rule_applies() {
+        let post = post();
+        let rules = [
+            rule("title", "golang", false, "hide"),
+            rule("tag", "rust", false, "highlight"),
+            rule("submitter", "alice", false, "dim"),
+        ];
+
+        assert_eq!(matching_rule(&rules, &post), Some(1));
+        assert_eq!(matching_rule(&rules[..1], &post), None);
+    }
+}
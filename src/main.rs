@@ -11,6 +11,7 @@ mod cli;
 mod config;
 mod data;
 mod database;
+mod filters;
 mod modes;
 mod panic;
 mod utils;
@@ -35,7 +36,14 @@ fn main() -> Result<()> {
 fn run() -> Result<i32> {
     let args = Args::parse();
     let db = init_db(&args.database)?;
-    let config = get_config(&args.config, args.clean)?;
+    let mut config = get_config(&args.config, args.clean)?;
+    if let Some(url) = args.url {
+        config.set_base_url(url);
+    }
+    if args.offline {
+        config.offline = true;
+    }
+
     let mut app = App::new(db, config)?;
 
     let mut terminal = ratatui::try_init()?;
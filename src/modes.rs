@@ -1,20 +1,177 @@
 use std::{borrow::Cow, fmt::Display, str::FromStr};
 
 use color_eyre::eyre::{Context, eyre};
+use reqwest::Url;
 use serde::{Deserialize, Deserializer};
 
-const URL: &str = "https://lobste.rs";
+/// Base URL of the lobste.rs instance used when none is configured.
+pub const DEFAULT_BASE_URL: &str = "https://lobste.rs";
 // Treat pages as 1-indexed - while 0 works, it gives the same results as 1 so
 // may as well skip it
-const STARTING_PAGE: u8 = 1;
+pub const STARTING_PAGE: u8 = 1;
 
-/// Modes used for selecting API endpoint to fetch data from. Inner values
-/// represent the page number.
+/// Time periods for the top mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TopPeriod {
+    Day,
+    #[default]
+    Week,
+    Month,
+    Year,
+}
+
+impl TopPeriod {
+    /// Get the path segment used for this period in the URL for the top mode.
+    fn url_segment(self) -> &'static str {
+        match self {
+            Self::Day => "1d",
+            Self::Week => "1w",
+            Self::Month => "1m",
+            Self::Year => "1y",
+        }
+    }
+
+    /// Cycle to the next, longer, period.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Day => Self::Week,
+            Self::Week => Self::Month,
+            Self::Month => Self::Year,
+            Self::Year => Self::Day,
+        }
+    }
+}
+
+impl FromStr for TopPeriod {
+    type Err = color_eyre::Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "1d" | "day" => Ok(Self::Day),
+            "1w" | "week" => Ok(Self::Week),
+            "1m" | "month" => Ok(Self::Month),
+            "1y" | "year" => Ok(Self::Year),
+            _ => Err(eyre!("Not a valid period: '{s}'")),
+        }
+    }
+}
+
+impl Display for TopPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Day => "day",
+                Self::Week => "week",
+                Self::Month => "month",
+                Self::Year => "year",
+            }
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for TopPeriod {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = Cow::<'de, str>::deserialize(deserializer)?;
+        TopPeriod::from_str(&s)
+            .context("Possible periods: 1d, 1w, 1m, 1y")
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Orders in which saved posts can be listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SavedSort {
+    /// Most recently saved first
+    #[default]
+    Date,
+    /// Highest score (when saved) first
+    Score,
+    /// Alphabetically by title
+    Title,
+}
+
+impl SavedSort {
+    /// Cycle to the next order.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Date => Self::Score,
+            Self::Score => Self::Title,
+            Self::Title => Self::Date,
+        }
+    }
+}
+
+impl FromStr for SavedSort {
+    type Err = color_eyre::Report;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "date" => Ok(Self::Date),
+            "score" => Ok(Self::Score),
+            "title" => Ok(Self::Title),
+            _ => Err(eyre!("Not a valid order: '{s}'")),
+        }
+    }
+}
+
+impl Display for SavedSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Date => "date",
+                Self::Score => "score",
+                Self::Title => "title",
+            }
+        )
+    }
+}
+
+impl<'de> Deserialize<'de> for SavedSort {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = Cow::<'de, str>::deserialize(deserializer)?;
+        SavedSort::from_str(&s)
+            .context("Possible orders: date, score, title")
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Modes used for selecting API endpoint to fetch data from. Inner `u8`
+/// values represent the page number.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Mode {
     Newest(u8),
     Hottest(u8),
     Active(u8),
+    /// Top posts within the given period of time.
+    Top {
+        period: TopPeriod,
+        page: u8,
+    },
+    /// Most recent comments across all posts.
+    Comments(u8),
+    /// Posts tagged with any of the given tags.
+    Tag(Vec<String>, u8),
+    /// Posts linking to the given domain.
+    Domain(String, u8),
+    /// Posts submitted by the given user.
+    UserStories(String, u8),
+    /// Comments written by the given user.
+    UserThreads(String, u8),
+    /// Posts which have been read, most recently read first.
+    History(u8),
+    /// Posts saved to read later, in the given order.
+    Saved {
+        sort: SavedSort,
+        page: u8,
+    },
 }
 
 impl Default for Mode {
@@ -24,76 +181,309 @@ impl Default for Mode {
 }
 
 impl Mode {
-    /// Get the URL for the endpoint corresponding to this mode.
-    pub fn get_url(&self) -> String {
+    /// Get the URL for the endpoint corresponding to this mode, on the instance
+    /// with the given base URL.
+    pub fn get_url(&self, base_url: &str) -> String {
+        match self {
+            Self::Hottest(page) => format!("{base_url}/page/{page}.json"),
+            Self::Newest(page) => format!("{base_url}/newest/page/{page}.json"),
+            Self::Active(page) => format!("{base_url}/active/page/{page}.json"),
+            Self::Top { period, page } => {
+                format!("{base_url}/top/{}/page/{page}.json", period.url_segment())
+            }
+            Self::Comments(page) => format!("{base_url}/comments/page/{page}.json"),
+            Self::Tag(tags, page) => format!("{base_url}/t/{}/page/{page}.json", tags.join(",")),
+            Self::Domain(domain, page) => format!("{base_url}/domains/{domain}/page/{page}.json"),
+            Self::UserStories(user, page) => format!("{base_url}/~{user}/stories/page/{page}.json"),
+            Self::UserThreads(user, page) => format!("{base_url}/~{user}/threads.json?page={page}"),
+            // Not fetched, so only identifies the listing
+            Self::History(page) => format!("{base_url}/history/page/{page}"),
+            Self::Saved { sort, page } => format!("{base_url}/saved/{sort}/page/{page}"),
+        }
+    }
+
+    /// Path of the listing for this mode relative to the base URL of an
+    /// instance, identifying it in the offline cache.
+    pub fn path(&self) -> String {
+        self.get_url("")
+    }
+
+    /// Whether this mode lists results stored locally, rather than fetched from
+    /// an instance.
+    pub fn is_local(&self) -> bool {
+        matches!(self, Self::History(_) | Self::Saved { .. })
+    }
+
+    /// Whether this mode lists comments rather than posts.
+    pub fn lists_comments(&self) -> bool {
+        matches!(self, Self::Comments(_) | Self::UserThreads(..))
+    }
+
+    /// Mutable reference to the (1-indexed) page number stored in this mode.
+    fn page_mut(&mut self) -> &mut u8 {
         match self {
-            Self::Hottest(page) => format!("{URL}/page/{page}.json"),
-            Self::Newest(page) => format!("{URL}/newest/page/{page}.json"),
-            Self::Active(page) => format!("{URL}/active/page/{page}.json"),
+            Self::Hottest(page)
+            | Self::Newest(page)
+            | Self::Active(page)
+            | Self::Top { page, .. }
+            | Self::Comments(page)
+            | Self::Tag(_, page)
+            | Self::Domain(_, page)
+            | Self::UserStories(_, page)
+            | Self::UserThreads(_, page)
+            | Self::History(page)
+            | Self::Saved { page, .. } => page,
         }
     }
 
     /// Increment the page for this mode, if possible. Returns true if the page
     /// changed.
     pub fn next_page(&mut self) -> bool {
-        match self {
-            Self::Hottest(page) | Self::Newest(page) | Self::Active(page) => {
-                let prev = *page;
-                *page = page.saturating_add(1);
-                // (*page ^ *page) == 0
-                *page != prev
-            }
-        }
+        let page = self.page_mut();
+        let prev = *page;
+        *page = page.saturating_add(1);
+        // (*page ^ *page) == 0
+        *page != prev
+    }
+
+    /// Set the (1-indexed) page for this mode. Returns true if the page
+    /// changed.
+    pub fn set_page(&mut self, page: u8) -> bool {
+        let current = self.page_mut();
+        let prev = *current;
+        *current = page.max(STARTING_PAGE);
+        *current != prev
     }
 
     /// Decrement the page for this mode, if possible. Returns true if the page
     /// changed.
     pub fn prev_page(&mut self) -> bool {
-        match self {
-            Self::Hottest(page) | Self::Newest(page) | Self::Active(page) => {
-                if *page > STARTING_PAGE {
-                    *page -= 1;
-                    true
-                } else {
-                    false
-                }
-            }
+        let page = self.page_mut();
+        if *page > STARTING_PAGE {
+            *page -= 1;
+            true
+        } else {
+            false
         }
     }
 
-    /// Cycle to the next mode.
-    pub fn next_mode(&mut self) {
+    /// Cycle to the next mode. The top mode is entered with the given period,
+    /// and the tag mode is only included in the cycle if `tags` is not empty.
+    pub fn next_mode(&mut self, tags: &[String], period: TopPeriod) {
         *self = match self {
             Self::Hottest(_) => Self::Newest(STARTING_PAGE),
             Self::Newest(_) => Self::Active(STARTING_PAGE),
-            Self::Active(_) => Self::Hottest(STARTING_PAGE),
+            Self::Active(_) => Self::Top {
+                period,
+                page: STARTING_PAGE,
+            },
+            Self::Top { .. } => Self::Comments(STARTING_PAGE),
+            Self::Comments(_) if !tags.is_empty() => Self::Tag(tags.to_vec(), STARTING_PAGE),
+            // End of the cycle, or a mode outside of it
+            _ => Self::Hottest(STARTING_PAGE),
         };
     }
 
-    /// Cycle to the previous mode.
-    pub fn prev_mode(&mut self) {
+    /// Cycle to the previous mode. The top mode is entered with the given
+    /// period, and the tag mode is only included in the cycle if `tags` is not
+    /// empty.
+    pub fn prev_mode(&mut self, tags: &[String], period: TopPeriod) {
         *self = match self {
             Self::Newest(_) => Self::Hottest(STARTING_PAGE),
-            Self::Hottest(_) => Self::Active(STARTING_PAGE),
             Self::Active(_) => Self::Newest(STARTING_PAGE),
+            Self::Top { .. } => Self::Active(STARTING_PAGE),
+            Self::Comments(_) => Self::Top {
+                period,
+                page: STARTING_PAGE,
+            },
+            // Start of the cycle, or a mode outside of it
+            _ if !tags.is_empty() && !matches!(self, Self::Tag(..)) => {
+                Self::Tag(tags.to_vec(), STARTING_PAGE)
+            }
+            _ => Self::Comments(STARTING_PAGE),
         };
     }
 
     /// Get the page number stored in this mode, converting it to a 0-indexed
     /// value.
     pub fn get_page(&self) -> usize {
-        let (Self::Hottest(page) | Self::Newest(page) | Self::Active(page)) = self;
+        let page = match self {
+            Self::Hottest(page)
+            | Self::Newest(page)
+            | Self::Active(page)
+            | Self::Top { page, .. }
+            | Self::Comments(page)
+            | Self::Tag(_, page)
+            | Self::Domain(_, page)
+            | Self::UserStories(_, page)
+            | Self::UserThreads(_, page)
+            | Self::History(page)
+            | Self::Saved { page, .. } => page,
+        };
         (*page as usize) - 1
     }
 }
 
+/// Parse a comma separated list of tags, e.g. `rust,databases`.
+fn parse_tags(s: &str) -> color_eyre::Result<Vec<String>> {
+    let tags: Vec<String> = s
+        .split(',')
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    if tags.is_empty() {
+        return Err(eyre!("No tags given"));
+    }
+
+    if let Some(tag) = tags.iter().find(|t| {
+        !t.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }) {
+        return Err(eyre!("Not a valid tag: '{tag}'"));
+    }
+
+    Ok(tags)
+}
+
+/// Deserialize a list of tags, validating and normalising them in the same way
+/// as for the tag mode.
+pub fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tags = Vec::<String>::deserialize(deserializer)?;
+    if tags.is_empty() {
+        return Ok(tags);
+    }
+
+    parse_tags(&tags.join(",")).map_err(serde::de::Error::custom)
+}
+
+/// Parse a domain, e.g. `lobste.rs`. A leading `www.` is ignored, as it is by
+/// lobste.rs itself.
+pub fn parse_domain(s: &str) -> color_eyre::Result<String> {
+    let domain = s.trim().to_lowercase();
+    let domain = domain.strip_prefix("www.").unwrap_or(&domain);
+
+    if domain.is_empty() {
+        return Err(eyre!("No domain given, expected e.g. 'domain:lobste.rs'"));
+    }
+
+    if domain.starts_with('.')
+        || !domain
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    {
+        return Err(eyre!(
+            "Not a valid domain: '{domain}', expected just the host name, e.g. 'lobste.rs'"
+        ));
+    }
+
+    Ok(domain.to_owned())
+}
+
+/// Parse a lobste.rs username, e.g. `~jcs` or `jcs`.
+pub fn parse_user(s: &str) -> color_eyre::Result<String> {
+    let user = s.trim();
+    let user = user.strip_prefix('~').unwrap_or(user);
+
+    if user.is_empty()
+        || !user
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(eyre!("Not a valid username: '{user}'"));
+    }
+
+    Ok(user.to_owned())
+}
+
+/// Parse the base URL of an instance running the lobste.rs codebase, e.g.
+/// `https://lobste.rs/`, normalising it by removing any trailing slash.
+pub fn parse_base_url(s: &str) -> color_eyre::Result<String> {
+    let url = Url::parse(s.trim()).context("Not a valid URL")?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(eyre!("URL must use http or https: '{url}'"));
+    }
+    if url.query().is_some() || url.fragment().is_some() {
+        return Err(eyre!("URL must not have a query or fragment: '{url}'"));
+    }
+
+    Ok(url.as_str().trim_end_matches('/').to_owned())
+}
+
 impl FromStr for Mode {
     type Err = color_eyre::Report;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, None)
+    }
+}
+
+impl Mode {
+    /// Parse a mode as `from_str` does, but with `domain` (e.g. the selected
+    /// story's host) used by a `domain` mode given no domain of its own.
+    pub fn parse(s: &str, domain: Option<&str>) -> color_eyre::Result<Self> {
+        if let Some((prefix, period)) = s.split_once(':')
+            && prefix.trim().eq_ignore_ascii_case("top")
+        {
+            return Ok(Self::Top {
+                period: TopPeriod::from_str(period)?,
+                page: STARTING_PAGE,
+            });
+        }
+
+        if let Some((prefix, sort)) = s.split_once(':')
+            && prefix.trim().eq_ignore_ascii_case("saved")
+        {
+            return Ok(Self::Saved {
+                sort: SavedSort::from_str(sort)?,
+                page: STARTING_PAGE,
+            });
+        }
+
+        if let Some((prefix, tags)) = s.split_once(':')
+            && prefix.trim().eq_ignore_ascii_case("tag")
+        {
+            return Ok(Self::Tag(parse_tags(tags)?, STARTING_PAGE));
+        }
+
+        let given = match s.split_once(':') {
+            Some((prefix, given)) if prefix.trim().eq_ignore_ascii_case("domain") => Some(given),
+            None if s.trim().eq_ignore_ascii_case("domain") => Some(""),
+            _ => None,
+        };
+        if let Some(given) = given {
+            let given = match domain {
+                Some(domain) if given.trim().is_empty() => domain,
+                _ => given,
+            };
+            return Ok(Self::Domain(parse_domain(given)?, STARTING_PAGE));
+        }
+
+        if let Some((prefix, user)) = s.split_once(':') {
+            match prefix.trim().to_lowercase().as_str() {
+                "stories" => return Ok(Self::UserStories(parse_user(user)?, STARTING_PAGE)),
+                "threads" => return Ok(Self::UserThreads(parse_user(user)?, STARTING_PAGE)),
+                _ => {}
+            }
+        }
+
         match s.to_lowercase().as_str() {
             "newest" => Ok(Self::Newest(STARTING_PAGE)),
             "hottest" => Ok(Self::Hottest(STARTING_PAGE)),
             "active" => Ok(Self::Active(STARTING_PAGE)),
+            "comments" => Ok(Self::Comments(STARTING_PAGE)),
+            "history" => Ok(Self::History(STARTING_PAGE)),
+            "saved" => Ok(Self::Saved {
+                sort: SavedSort::default(),
+                page: STARTING_PAGE,
+            }),
+            "top" => Ok(Self::Top {
+                period: TopPeriod::default(),
+                page: STARTING_PAGE,
+            }),
             _ => Err(eyre!("Not a valid mode")),
         }
     }
@@ -101,15 +491,19 @@ impl FromStr for Mode {
 
 impl Display for Mode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::Hottest(_) => "Hottest",
-                Self::Newest(_) => "Newest",
-                Self::Active(_) => "Active",
-            }
-        )
+        match self {
+            Self::Hottest(_) => write!(f, "Hottest"),
+            Self::Newest(_) => write!(f, "Newest"),
+            Self::Active(_) => write!(f, "Active"),
+            Self::Top { period, .. } => write!(f, "Top ({period})"),
+            Self::Comments(_) => write!(f, "Comments"),
+            Self::Tag(tags, _) => write!(f, "Tag ({})", tags.join(", ")),
+            Self::Domain(domain, _) => write!(f, "Domain ({domain})"),
+            Self::UserStories(user, _) => write!(f, "Stories (~{user})"),
+            Self::UserThreads(user, _) => write!(f, "Threads (~{user})"),
+            Self::History(_) => write!(f, "History"),
+            Self::Saved { sort, .. } => write!(f, "Saved (by {sort})"),
+        }
     }
 }
 
@@ -120,7 +514,9 @@ impl<'de> Deserialize<'de> for Mode {
     {
         let s = Cow::<'de, str>::deserialize(deserializer)?;
         Mode::from_str(&s)
-            .context("Possible modes: newest, hottest, active")
+            .context(
+                "Possible modes: newest, hottest, active, top, top:<1d|1w|1m|1y>, comments, tag:<tag1>,<tag2>,..., domain:<domain>, stories:<user>, threads:<user>, history, saved, saved:<date|score|title>",
+            )
             .map_err(serde::de::Error::custom)
     }
 }
@@ -155,32 +551,286 @@ mod test {
 
     #[test]
     fn changing_mode_resets_page() {
+        let period = TopPeriod::default();
+        let top = Mode::Top {
+            period,
+            page: STARTING_PAGE,
+        };
+
         let mut mode = Mode::default();
         assert!(matches!(mode, Mode::Hottest(STARTING_PAGE)));
         assert!(mode.next_page());
 
-        mode.next_mode();
+        mode.next_mode(&[], period);
         assert_eq!(mode, Mode::Newest(STARTING_PAGE));
         assert!(mode.next_page());
 
-        mode.next_mode();
+        mode.next_mode(&[], period);
         assert_eq!(mode, Mode::Active(STARTING_PAGE));
         assert!(mode.next_page());
 
-        mode.next_mode();
+        mode.next_mode(&[], period);
+        assert_eq!(mode, top);
+        assert!(mode.next_page());
+
+        mode.next_mode(&[], period);
+        assert_eq!(mode, Mode::Comments(STARTING_PAGE));
+        assert!(mode.next_page());
+
+        mode.next_mode(&[], period);
         assert_eq!(mode, Mode::default());
         assert!(mode.next_page());
 
-        mode.prev_mode();
+        mode.prev_mode(&[], period);
+        assert_eq!(mode, Mode::Comments(STARTING_PAGE));
+        assert!(mode.next_page());
+
+        mode.prev_mode(&[], period);
+        assert_eq!(mode, top);
+        assert!(mode.next_page());
+
+        mode.prev_mode(&[], period);
         assert_eq!(mode, Mode::Active(STARTING_PAGE));
         assert!(mode.next_page());
 
-        mode.prev_mode();
+        mode.prev_mode(&[], period);
         assert_eq!(mode, Mode::Newest(STARTING_PAGE));
         assert!(mode.next_page());
 
-        mode.prev_mode();
+        mode.prev_mode(&[], period);
+        assert_eq!(mode, Mode::default());
+        assert!(mode.next_page());
+    }
+
+    #[test]
+    fn tag_mode_in_cycle() {
+        let period = TopPeriod::default();
+        let tags = vec![String::from("rust"), String::from("databases")];
+        let tag_mode = Mode::Tag(tags.clone(), STARTING_PAGE);
+
+        let mut mode = Mode::Comments(STARTING_PAGE);
+        mode.next_mode(&tags, period);
+        assert_eq!(mode, tag_mode);
+        assert!(mode.next_page());
+        assert_eq!(mode.get_page(), 1);
+
+        mode.next_mode(&tags, period);
+        assert_eq!(mode, Mode::default());
+
+        mode.prev_mode(&tags, period);
+        assert_eq!(mode, tag_mode);
+
+        mode.prev_mode(&tags, period);
+        assert_eq!(mode, Mode::Comments(STARTING_PAGE));
+    }
+
+    #[test]
+    fn top_mode() {
+        let mode = Mode::from_str("top:1m").unwrap();
+        assert_eq!(
+            mode,
+            Mode::Top {
+                period: TopPeriod::Month,
+                page: STARTING_PAGE
+            }
+        );
+        assert_eq!(
+            mode.get_url(DEFAULT_BASE_URL),
+            format!("{DEFAULT_BASE_URL}/top/1m/page/1.json")
+        );
+        assert_eq!(mode.to_string(), "Top (month)");
+
+        assert_eq!(
+            Mode::from_str("top").unwrap(),
+            Mode::Top {
+                period: TopPeriod::default(),
+                page: STARTING_PAGE
+            }
+        );
+        assert!(Mode::from_str("top:2w").is_err());
+
+        // Entering the top mode uses the given period
+        let mut mode = Mode::Active(STARTING_PAGE);
+        mode.next_mode(&[], TopPeriod::Day);
+        assert_eq!(
+            mode,
+            Mode::Top {
+                period: TopPeriod::Day,
+                page: STARTING_PAGE
+            }
+        );
+
+        let mut period = TopPeriod::Day;
+        for expected in [
+            TopPeriod::Week,
+            TopPeriod::Month,
+            TopPeriod::Year,
+            TopPeriod::Day,
+        ] {
+            period = period.next();
+            assert_eq!(period, expected);
+        }
+    }
+
+    #[test]
+    fn parse_tag_mode() {
+        let expected = Mode::Tag(
+            vec![String::from("rust"), String::from("databases")],
+            STARTING_PAGE,
+        );
+        assert_eq!(Mode::from_str("tag:rust,databases").unwrap(), expected);
+        assert_eq!(Mode::from_str("TAG: Rust , databases,").unwrap(), expected);
+        assert_eq!(
+            expected.get_url(DEFAULT_BASE_URL),
+            format!("{DEFAULT_BASE_URL}/t/rust,databases/page/1.json")
+        );
+
+        assert!(Mode::from_str("tag:").is_err());
+        assert!(Mode::from_str("tag:,").is_err());
+        assert!(Mode::from_str("tag:rust/../..").is_err());
+    }
+
+    #[test]
+    fn domain_mode() {
+        let mode = Mode::from_str("domain:www.Example.com").unwrap();
+        assert_eq!(
+            mode,
+            Mode::Domain(String::from("example.com"), STARTING_PAGE)
+        );
+        assert_eq!(
+            mode.get_url(DEFAULT_BASE_URL),
+            format!("{DEFAULT_BASE_URL}/domains/example.com/page/1.json")
+        );
+
+        // Without a domain, the selected story's host is used
+        assert_eq!(
+            Mode::parse("domain: ", Some("lobste.rs")).unwrap(),
+            Mode::Domain(String::from("lobste.rs"), STARTING_PAGE)
+        );
+        assert_eq!(
+            Mode::parse("domain", Some("lobste.rs")).unwrap(),
+            Mode::Domain(String::from("lobste.rs"), STARTING_PAGE)
+        );
+        let err = Mode::parse("domain:", None).unwrap_err();
+        assert!(err.to_string().contains("No domain given"));
+        assert!(Mode::from_str("domain:www.").is_err());
+        assert!(Mode::from_str("domain:example.com/path").is_err());
+
+        // Leaving the domain mode returns to the mode cycle
+        let period = TopPeriod::default();
+        let mut next = mode.clone();
+        next.next_mode(&[], period);
+        assert_eq!(next, Mode::default());
+        let mut prev = mode.clone();
+        prev.prev_mode(&[], period);
+        assert_eq!(prev, Mode::Comments(STARTING_PAGE));
+    }
+
+    #[test]
+    fn user_modes() {
+        let stories = Mode::from_str("stories:~jcs").unwrap();
+        assert_eq!(
+            stories,
+            Mode::UserStories(String::from("jcs"), STARTING_PAGE)
+        );
+        assert_eq!(
+            stories.get_url(DEFAULT_BASE_URL),
+            format!("{DEFAULT_BASE_URL}/~jcs/stories/page/1.json")
+        );
+        assert!(!stories.lists_comments());
+
+        let mut threads = Mode::from_str("threads:jcs").unwrap();
+        assert_eq!(
+            threads,
+            Mode::UserThreads(String::from("jcs"), STARTING_PAGE)
+        );
+        assert!(threads.lists_comments());
+        assert!(threads.next_page());
+        assert_eq!(
+            threads.get_url(DEFAULT_BASE_URL),
+            format!("{DEFAULT_BASE_URL}/~jcs/threads.json?page=2")
+        );
+
+        assert!(Mode::from_str("stories:").is_err());
+        assert!(Mode::from_str("threads:jcs/../admin").is_err());
+    }
+
+    #[test]
+    fn history_mode() {
+        let mut mode = Mode::from_str("history").unwrap();
+        assert_eq!(mode, Mode::History(STARTING_PAGE));
+        assert!(mode.is_local());
+        assert!(!mode.lists_comments());
+        assert!(mode.next_page());
+        assert_eq!(mode.path(), "/history/page/2");
+
+        // Leaving the history mode returns to the mode cycle
+        mode.next_mode(&[], TopPeriod::default());
         assert_eq!(mode, Mode::default());
+        assert!(!mode.is_local());
+    }
+
+    #[test]
+    fn saved_mode() {
+        let mode = Mode::from_str("saved:score").unwrap();
+        assert_eq!(
+            mode,
+            Mode::Saved {
+                sort: SavedSort::Score,
+                page: STARTING_PAGE
+            }
+        );
+        assert!(mode.is_local());
+        assert_eq!(mode.to_string(), "Saved (by score)");
+        assert_eq!(
+            Mode::from_str("saved").unwrap(),
+            Mode::Saved {
+                sort: SavedSort::Date,
+                page: STARTING_PAGE
+            }
+        );
+        assert!(Mode::from_str("saved:votes").is_err());
+
+        let mut sort = SavedSort::Date;
+        for expected in [SavedSort::Score, SavedSort::Title, SavedSort::Date] {
+            sort = sort.next();
+            assert_eq!(sort, expected);
+        }
+    }
+
+    #[test]
+    fn comments_mode() {
+        let mut mode = Mode::from_str("comments").unwrap();
+        assert_eq!(mode, Mode::Comments(STARTING_PAGE));
+        assert!(mode.lists_comments());
         assert!(mode.next_page());
+        assert_eq!(
+            mode.get_url(DEFAULT_BASE_URL),
+            format!("{DEFAULT_BASE_URL}/comments/page/2.json")
+        );
+    }
+
+    #[test]
+    fn base_url() {
+        assert_eq!(
+            parse_base_url("https://lobste.rs/").unwrap(),
+            DEFAULT_BASE_URL
+        );
+        assert_eq!(
+            parse_base_url(" http://localhost:3000 ").unwrap(),
+            "http://localhost:3000"
+        );
+        assert_eq!(
+            parse_base_url("https://example.com/lobsters/").unwrap(),
+            "https://example.com/lobsters"
+        );
+        assert_eq!(
+            Mode::default().get_url("http://localhost:3000"),
+            "http://localhost:3000/page/1.json"
+        );
+
+        assert!(parse_base_url("lobste.rs").is_err());
+        assert!(parse_base_url("ftp://lobste.rs").is_err());
+        assert!(parse_base_url("https://lobste.rs/?page=1").is_err());
     }
 }
@@ -1,12 +1,22 @@
 //! Custom panic hook, derived from the code for the [panic crate](https://github.com/exact-labs/panic).
 
-use std::{borrow::Cow, collections::HashMap, error::Error, fmt::Write as FmtWrite, fs::File, io::{Result as IoResult, Write}, mem, panic::PanicHookInfo, path::{Path, PathBuf}};
+use std::{borrow::Cow, collections::HashMap, error::Error, fmt::Write as FmtWrite, fs::File, io::{Result as IoResult, Write}, mem, panic::PanicHookInfo, path::{Path, PathBuf}, sync::RwLock};
 
 pub use anstyle::AnsiColor as Color;
 use backtrace::Backtrace;
 use serde::Serialize;
 use uuid::Uuid;
 
+/// Base URL of the instance being browsed, included in reports.
+static INSTANCE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Set the base URL of the instance being browsed, to be included in reports.
+pub fn set_instance(url: &str) {
+    if let Ok(mut instance) = INSTANCE.write() {
+        *instance = Some(url.to_owned());
+    }
+}
+
 #[derive(Debug, Serialize, Clone, Copy)]
 pub enum Method {
     Panic,
@@ -17,6 +27,8 @@ pub struct Report {
     name: String,
     operating_system: String,
     crate_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance: Option<String>,
     explanation: String,
     cause: String,
     method: Method,
@@ -32,6 +44,7 @@ impl Report {
         cause: String,
     ) -> Self {
         let operating_system = os_info::get().to_string();
+        let instance = INSTANCE.read().ok().and_then(|i| i.clone());
 
         const SKIP_FRAMES_NUM: usize = 8;
         const HEX_WIDTH: usize = mem::size_of::<usize>() + 2;
@@ -81,6 +94,7 @@ impl Report {
             crate_version: version.into(),
             name: name.into(),
             operating_system,
+            instance,
             method,
             explanation,
             cause,
@@ -33,7 +33,7 @@ pub fn human_duration(duration: chrono::Duration) -> String {
 }
 
 /// Returns the given number of bytes as a human-readable string representation.
-pub fn human_bytes(mut bytes: u32) -> String {
+pub fn human_bytes(mut bytes: u64) -> String {
     let unit = if bytes < 1_000 {
         "B"
     } else if bytes < 1_000_000 {
@@ -66,6 +66,72 @@ pub fn truncate(s: &str, max_chars: usize) -> Cow<'_, str> {
     }
 }
 
+/// Score how well the pattern fuzzy matches the text (case insensitively), if
+/// its characters all appear in the text in order. Characters matched one
+/// after another, or at the start of a word (most of all the first word),
+/// score higher.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<usize> {
+    let mut score = 0;
+    let mut text = text.chars().map(|c| c.to_ascii_lowercase()).enumerate();
+    let mut prev: Option<(usize, char)> = None;
+
+    for p in pattern.chars().map(|c| c.to_ascii_lowercase()) {
+        if p.is_whitespace() {
+            continue;
+        }
+
+        let mut last = prev.map(|(_, c)| c);
+        let (i, _) = text.by_ref().find(|(_, c)| {
+            let found = *c == p;
+            if !found {
+                last = Some(*c);
+            }
+            found
+        })?;
+
+        score += 1;
+        if prev.is_some_and(|(j, _)| j + 1 == i) {
+            score += 2;
+        }
+        if last.is_none_or(|c| !c.is_alphanumeric()) {
+            score += 3;
+        }
+        if i == 0 {
+            score += 3;
+        }
+        prev = Some((i, p));
+    }
+
+    Some(score)
+}
+
+/// Indices of the characters in the text matching the pattern (case
+/// insensitively) - as a substring if the text contains it, otherwise if
+/// `fuzzy`, the first characters found in order. Empty patterns match nothing.
+pub fn match_indices(pattern: &str, text: &str, fuzzy: bool) -> Option<Vec<usize>> {
+    let pattern: Vec<char> = pattern.chars().map(|c| c.to_ascii_lowercase()).collect();
+    let text: Vec<char> = text.chars().map(|c| c.to_ascii_lowercase()).collect();
+    if pattern.is_empty() {
+        return None;
+    }
+
+    if let Some(start) = text.windows(pattern.len()).position(|w| w == pattern) {
+        return Some((start..start + pattern.len()).collect());
+    }
+    if !fuzzy {
+        return None;
+    }
+
+    let mut indices = Vec::with_capacity(pattern.len());
+    let mut text = text.iter().enumerate();
+    for p in &pattern {
+        let (i, _) = text.find(|(_, c)| *c == p)?;
+        indices.push(i);
+    }
+
+    Some(indices)
+}
+
 /// Utility function for centering a [`Rect`] given the horizontal and vertical
 /// constraints.
 pub fn center_area(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
@@ -116,6 +182,7 @@ mod test {
         assert_eq!(human_bytes(175_500_000), String::from("175MB"));
         assert_eq!(human_bytes(1_000_000_000), String::from("1GB"));
         assert_eq!(human_bytes(2_000_000_000), String::from("2GB"));
+        assert_eq!(human_bytes(12_500_000_000), String::from("12GB"));
     }
 
     #[test]
@@ -173,4 +240,29 @@ mod test {
             String::from("3 years")
         );
     }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "next_row"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "next_row"), None);
+        assert_eq!(fuzzy_score("wor", "next_row"), None);
+        assert!(fuzzy_score("NR", "next_row").is_some());
+
+        // Consecutive characters and word starts score higher
+        let score = |pattern| fuzzy_score(pattern, "next_row").unwrap();
+        assert!(score("next") > score("nxrw"));
+        assert!(score("nr") > score("ne"));
+        assert!(score("ro") > score("ow"));
+    }
+
+    #[test]
+    fn test_match_indices() {
+        assert_eq!(match_indices("", "Rust", true), None);
+        assert_eq!(match_indices("ST", "Rust", false), Some(vec![2, 3]));
+        assert_eq!(match_indices("rt", "Rust", false), None);
+        assert_eq!(match_indices("rt", "Rust", true), Some(vec![0, 3]));
+        // Prefers the substring to the first characters found
+        assert_eq!(match_indices("ab", "a_xab", true), Some(vec![3, 4]));
+        assert_eq!(match_indices("tr", "Rust", true), None);
+    }
 }